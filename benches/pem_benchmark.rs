@@ -29,6 +29,22 @@ fn pem_parse_many() {
     pem::parse_many(SAMPLE).unwrap();
 }
 
+fn pem_parse_many_bundle(bundle: &str) {
+    pem::parse_many(bundle).unwrap();
+}
+
+#[cfg(feature = "parallel")]
+fn pem_parse_many_parallel(bundle: &str) {
+    pem::parse_many_parallel(bundle).unwrap();
+}
+
+#[cfg(feature = "parallel")]
+fn pem_parse_many_pipelined(bundle: &str) {
+    pem::parse_many_pipelined(bundle)
+        .collect::<pem::Result<Vec<_>>>()
+        .unwrap();
+}
+
 fn pem_encode(pem: &pem::Pem) {
     pem::encode(pem);
 }
@@ -42,6 +58,20 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("pem::parse", |b| b.iter(pem_parse));
     c.bench_function("pem::parse_many", |b| b.iter(pem_parse_many));
 
+    // Parse large bundles
+    let bundle = SAMPLE.repeat(500);
+    c.bench_function("pem::parse_many (bundle)", |b| {
+        b.iter(|| pem_parse_many_bundle(&bundle))
+    });
+    #[cfg(feature = "parallel")]
+    c.bench_function("pem::parse_many_parallel (bundle)", |b| {
+        b.iter(|| pem_parse_many_parallel(&bundle))
+    });
+    #[cfg(feature = "parallel")]
+    c.bench_function("pem::parse_many_pipelined (bundle)", |b| {
+        b.iter(|| pem_parse_many_pipelined(&bundle))
+    });
+
     // Encode
     let pem = pem::Pem::new(
         "RSA PRIVATE KEY",
@@ -0,0 +1,140 @@
+use crate::{SectionKind, LEGACY_TAG_RENAMES};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// How tags are compared when looking for blocks of a given type
+///
+/// Tags are first mapped through a table of aliases to their canonical
+/// form, then compared exactly or, optionally, ignoring ASCII case. The
+/// default matcher has no aliases and compares exactly;
+/// [`TagMatcher::standard`] knows the legacy tags still emitted by various
+/// tools.
+///
+/// Used by [`parse_expecting_with`](crate::parse_expecting_with),
+/// [`read_items_with`](crate::read_items_with) and
+/// [`PemBundle::filter_by_tag_with`](crate::PemBundle::filter_by_tag_with).
+///
+/// # Example
+/// ```rust
+///  use pem::{SectionKind, TagMatcher};
+///
+///  let matcher = TagMatcher::standard()
+///      .add_alias("OPENSSL CERT", "CERTIFICATE")
+///      .set_case_insensitive(true);
+///  assert!(matcher.matches("X509 CERTIFICATE", "CERTIFICATE"));
+///  assert!(matcher.matches("openssl cert", "Certificate"));
+///  assert_eq!(matcher.kind("new certificate request"), SectionKind::CertificateRequest);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagMatcher {
+    /// Aliases and the canonical tags they stand for
+    aliases: Vec<(String, String)>,
+
+    /// Whether to ignore ASCII case
+    case_insensitive: bool,
+}
+
+impl TagMatcher {
+    /// Create a matcher comparing tags exactly, without aliases
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a matcher knowing the [`LEGACY_TAG_RENAMES`], and
+    /// `TRUSTED CERTIFICATE` as an alias of `CERTIFICATE`
+    ///
+    /// Note that OpenSSL appends its trust settings to the certificate in
+    /// `TRUSTED CERTIFICATE` blocks, after the DER certificate itself.
+    /// [`Item::from_pem_with`](crate::Item::from_pem_with) strips them.
+    pub fn standard() -> Self {
+        LEGACY_TAG_RENAMES
+            .iter()
+            .chain(&[("TRUSTED CERTIFICATE", "CERTIFICATE")])
+            .fold(Self::new(), |matcher, (alias, tag)| {
+                matcher.add_alias(alias, tag)
+            })
+    }
+
+    /// Treat `alias` as another name for `tag`
+    pub fn add_alias(mut self, alias: &str, tag: &str) -> Self {
+        self.aliases.push((alias.to_string(), tag.to_string()));
+        self
+    }
+
+    /// Set whether to ignore ASCII case when comparing tags and looking up
+    /// aliases
+    pub fn set_case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Get the canonical tag `tag` is an alias of, or `tag` itself
+    pub fn canonical<'a>(&'a self, tag: &'a str) -> &'a str {
+        self.aliases
+            .iter()
+            .find(|(alias, _)| self.eq(alias, tag))
+            .map_or(tag, |(_, canonical)| canonical)
+    }
+
+    /// Whether the two tags stand for the same type of block
+    pub fn matches(&self, a: &str, b: &str) -> bool {
+        self.eq(self.canonical(a), self.canonical(b))
+    }
+
+    /// Get the kind of block `tag` stands for
+    pub fn kind(&self, tag: &str) -> SectionKind {
+        let tag = self.canonical(tag);
+        match SectionKind::from(tag) {
+            // Well-known tags are all upper case
+            SectionKind::Other(other) if self.case_insensitive => {
+                match SectionKind::from(tag.to_ascii_uppercase().as_str()) {
+                    SectionKind::Other(_) => SectionKind::Other(other),
+                    kind => kind,
+                }
+            }
+            kind => kind,
+        }
+    }
+
+    fn eq(&self, a: &str, b: &str) -> bool {
+        if self.case_insensitive {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a == b
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tag_matcher() {
+        let exact = TagMatcher::new();
+        assert!(exact.matches("CERTIFICATE", "CERTIFICATE"));
+        assert!(!exact.matches("X509 CERTIFICATE", "CERTIFICATE"));
+        assert!(!exact.matches("certificate", "CERTIFICATE"));
+        assert_eq!(
+            exact.kind("certificate"),
+            SectionKind::Other("certificate".into())
+        );
+
+        let standard = TagMatcher::standard();
+        assert!(standard.matches("TRUSTED CERTIFICATE", "X509 CERTIFICATE"));
+        assert!(standard.matches("CRL", "X509 CRL"));
+        assert!(!standard.matches("trusted certificate", "CERTIFICATE"));
+        assert_eq!(standard.kind("X.509 CERTIFICATE"), SectionKind::Certificate);
+
+        let lax = standard.set_case_insensitive(true);
+        assert!(lax.matches("trusted certificate", "Certificate"));
+        assert!(!lax.matches("PRIVATE KEY", "RSA PRIVATE KEY"));
+        assert_eq!(lax.canonical("crl"), "X509 CRL");
+        assert_eq!(lax.kind("Private Key"), SectionKind::PrivateKey);
+        assert_eq!(lax.kind("Foo"), SectionKind::Other("Foo".into()));
+    }
+}
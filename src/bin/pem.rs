@@ -0,0 +1,146 @@
+//! Command line tool for splitting, joining, inspecting and converting
+//! PEM files, built with the `cli` feature.
+use pem::{encode_config, EncodeConfig, LineEnding, Pem, PemReader};
+use std::{
+    env,
+    fs::File,
+    io::{self, Read, Write},
+    path::Path,
+    process,
+};
+
+const USAGE: &str = "\
+usage: pem <command> [args]
+
+commands:
+  split <file> [<dir>]            write each block to its own file in <dir>
+  join <file>...                  concatenate the blocks of the files
+  inspect <file>...               list the tag, size and SHA-256 fingerprint of each block
+  to-der <file> [<index>]         write the contents of a block as binary DER
+  from-der <tag> <file>           wrap binary DER in a block with the given tag
+
+Use - to read from stdin. Output goes to stdout, except for split.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["split", file] => split(file, "."),
+        ["split", file, dir] => split(file, dir),
+        ["join", files @ ..] if !files.is_empty() => join(files),
+        ["inspect", files @ ..] if !files.is_empty() => inspect(files),
+        ["to-der", file] => to_der(file, "0"),
+        ["to-der", file, index] => to_der(file, index),
+        ["from-der", tag, file] => from_der(tag, file),
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{USAGE}");
+            return;
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            process::exit(2);
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("pem: {e}");
+        process::exit(1);
+    }
+}
+
+fn open(file: &str) -> io::Result<Box<dyn Read>> {
+    if file == "-" {
+        Ok(Box::new(io::stdin()))
+    } else {
+        let reader = File::open(file).map_err(|e| annotate(file, e))?;
+        Ok(Box::new(reader))
+    }
+}
+
+fn annotate(file: &str, e: io::Error) -> io::Error {
+    io::Error::new(e.kind(), format!("{file}: {e}"))
+}
+
+/// Read the blocks of a file as they are parsed
+fn blocks(file: &str) -> io::Result<impl Iterator<Item = io::Result<Pem>> + '_> {
+    let reader = PemReader::new(open(file)?);
+    Ok(reader.map(move |result| result.map_err(|e| annotate(file, e))))
+}
+
+fn config() -> EncodeConfig {
+    EncodeConfig::new().set_line_ending(LineEnding::LF)
+}
+
+fn split(file: &str, dir: &str) -> io::Result<()> {
+    let stem = match file {
+        "-" => "stdin",
+        _ => Path::new(file)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("block"),
+    };
+    for (i, pem) in blocks(file)?.enumerate() {
+        // Written atomically, and with mode 0600 for private keys
+        let path = Path::new(dir).join(format!("{stem}-{i}.pem"));
+        pem?.write_to_file(&path)?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn join(files: &[&str]) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    for file in files {
+        for pem in blocks(file)? {
+            stdout.write_all(encode_config(&pem?, config()).as_bytes())?;
+        }
+    }
+    stdout.flush()
+}
+
+fn inspect(files: &[&str]) -> io::Result<()> {
+    for file in files {
+        for (i, pem) in blocks(file)?.enumerate() {
+            let pem = pem?;
+            println!(
+                "{file}[{i}]: {} ({} bytes) SHA256 {}",
+                pem.tag(),
+                pem.contents().len(),
+                pem.sha256_fingerprint()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn to_der(file: &str, index: &str) -> io::Result<()> {
+    let index: usize = index.parse().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid index: {index}"),
+        )
+    })?;
+    let pem = blocks(file)?.nth(index).unwrap_or_else(|| {
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{file}: no block at index {index}"),
+        ))
+    })?;
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    stdout.write_all(pem.contents())?;
+    stdout.flush()
+}
+
+fn from_der(tag: &str, file: &str) -> io::Result<()> {
+    let mut contents = Vec::new();
+    open(file)?
+        .read_to_end(&mut contents)
+        .map_err(|e| annotate(file, e))?;
+    let pem =
+        Pem::try_new(tag, contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    stdout.write_all(encode_config(&pem, config()).as_bytes())?;
+    stdout.flush()
+}
@@ -0,0 +1,211 @@
+use crate::{parse_captures, parse_captures_iter, Captures, Pem, PemError, Result};
+use core::str;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{string::String, vec::Vec};
+
+/// A PEM block borrowed from the input, as returned by [`parse_ref`]
+///
+/// Only the framing is validated; the headers and the base64 data are
+/// left untouched until [`PemRef::decode`] is called, so looking at the
+/// tags of a bundle doesn't allocate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PemRef<'a> {
+    tag: &'a str,
+    headers: &'a [u8],
+    data: &'a [u8],
+}
+
+impl<'a> PemRef<'a> {
+    pub(crate) fn from_captures(caps: Captures<'a>) -> Result<Self> {
+        let tag = str::from_utf8(caps.begin).map_err(PemError::NotUtf8)?;
+        if tag.is_empty() {
+            return Err(PemError::MissingBeginTag);
+        }
+        let tag_end = str::from_utf8(caps.end).map_err(PemError::NotUtf8)?;
+        if tag_end.is_empty() {
+            return Err(PemError::MissingEndTag);
+        }
+        if tag != tag_end {
+            return Err(PemError::MismatchedTags(tag.into(), tag_end.into()));
+        }
+        Ok(PemRef {
+            tag,
+            headers: caps.headers,
+            data: caps.data,
+        })
+    }
+
+    /// Get the tag of the block
+    pub fn tag(&self) -> &'a str {
+        self.tag
+    }
+
+    /// Get the raw RFC 1421 header lines of the block, if any
+    pub fn raw_headers(&self) -> &'a [u8] {
+        self.headers
+    }
+
+    /// Get the raw base64 data of the block, including line breaks
+    pub fn raw_data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Get the base64 data of the block without whitespace, to forward it
+    /// without decoding it
+    ///
+    /// # Example
+    /// ```rust
+    ///  use pem::parse_ref;
+    ///
+    ///  let input = "-----BEGIN FOO-----\r\nAQID\r\nBAU=\r\n-----END FOO-----\r\n";
+    ///  assert_eq!(parse_ref(input).unwrap().contents_base64().unwrap(), "AQIDBAU=");
+    /// ```
+    pub fn contents_base64(&self) -> Result<String> {
+        let data = str::from_utf8(self.data).map_err(PemError::NotUtf8)?;
+        Ok(data.chars().filter(|c| !c.is_whitespace()).collect())
+    }
+
+    /// Decode the base64 data straight into `writer`, returning the number
+    /// of bytes written
+    ///
+    /// The data is decoded a few kilobytes at a time, so even huge blocks
+    /// only need a small buffer. Headers are skipped. Invalid data is
+    /// reported as an error of kind [`io::ErrorKind::InvalidData`], but
+    /// whatever was decoded before it has already been written.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use pem::parse_ref;
+    ///
+    ///  let input = "-----BEGIN FOO-----\r\nAQID\r\nBAU=\r\n-----END FOO-----\r\n";
+    ///  let mut output = Vec::new();
+    ///  let len = parse_ref(input).unwrap().decode_contents_to(&mut output).unwrap();
+    ///  assert_eq!(len, 5);
+    ///  assert_eq!(output, [1, 2, 3, 4, 5]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decode_contents_to<W: Write>(&self, writer: W) -> io::Result<u64> {
+        crate::buffer::decode_data_to(self.data, writer)
+    }
+
+    /// Decode the headers and the base64 data into an owned Pem struct
+    pub fn decode(&self) -> Result<Pem> {
+        Pem::new_from_captures(Captures {
+            begin: self.tag.as_bytes(),
+            headers: self.headers,
+            data: self.data,
+            end: self.tag.as_bytes(),
+        })
+    }
+}
+
+/// Parses a single PEM block without decoding it or copying any data
+///
+/// # Example
+/// ```rust
+///  use pem::parse_ref;
+///
+///  let input = "-----BEGIN CERTIFICATE-----\nAQID\n-----END CERTIFICATE-----\n";
+///  let pem = parse_ref(input).unwrap();
+///  assert_eq!(pem.tag(), "CERTIFICATE");
+///  assert_eq!(pem.raw_data(), b"AQID\n");
+///  assert_eq!(pem.decode().unwrap().contents(), [1, 2, 3]);
+/// ```
+pub fn parse_ref<B: AsRef<[u8]> + ?Sized>(input: &B) -> Result<PemRef<'_>> {
+    parse_captures(input.as_ref())
+        .ok_or(PemError::MalformedFraming)
+        .and_then(PemRef::from_captures)
+}
+
+/// Parses a set of PEM blocks without decoding them or copying any data
+///
+/// # Example
+/// ```rust
+///  use pem::parse_many_ref;
+///
+///  let input = "-----BEGIN KEY-----\nAQID\n-----END KEY-----\n\
+///               -----BEGIN CERTIFICATE-----\nBAUG\n-----END CERTIFICATE-----\n";
+///  let certs: Vec<_> = parse_many_ref(input)
+///      .unwrap()
+///      .into_iter()
+///      .filter(|pem| pem.tag() == "CERTIFICATE")
+///      .map(|pem| pem.decode())
+///      .collect::<pem::Result<_>>()
+///      .unwrap();
+///  assert_eq!(certs.len(), 1);
+/// ```
+pub fn parse_many_ref<B: AsRef<[u8]> + ?Sized>(input: &B) -> Result<Vec<PemRef<'_>>> {
+    parse_captures_iter(input.as_ref())
+        .map(PemRef::from_captures)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode, parse, parse_many, LazyPem};
+
+    const INPUT: &str = "-----BEGIN FOO-----\r\n\
+                         Comment: first\r\n\
+                         \r\n\
+                         AQID\r\n\
+                         -----END FOO-----\r\n\
+                         -----BEGIN BAR-----\r\nBA?G\r\n-----END BAR-----\r\n";
+
+    #[test]
+    fn test_parse_many_ref_defers_decoding() {
+        let refs = parse_many_ref(INPUT).unwrap();
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].tag(), "FOO");
+        assert_eq!(refs[0].raw_headers(), b"Comment: first");
+        let mut foo = Pem::new("FOO", [1, 2, 3]);
+        foo.headers_mut().add("Comment", "first").unwrap();
+        assert_eq!(refs[0].decode(), Ok(foo));
+        assert_eq!(refs[1].tag(), "BAR");
+        assert!(matches!(refs[1].decode(), Err(PemError::InvalidData(_))));
+        assert_eq!(refs[0].contents_base64().unwrap(), "AQID");
+        assert_eq!(refs[1].contents_base64().unwrap(), "BA?G");
+        let lazy = LazyPem::from_base64_parts("FOO", &refs[0].contents_base64().unwrap());
+        assert_eq!(lazy.contents_base64().unwrap(), "AQID");
+        assert!(parse_many(INPUT).is_err());
+    }
+
+    #[test]
+    fn test_parse_ref_framing_errors() {
+        assert_eq!(
+            parse_ref("-----BEGIN FOO-----\nAQID\n-----END BAR-----\n"),
+            Err(PemError::MismatchedTags("FOO".into(), "BAR".into()))
+        );
+        assert_eq!(parse_ref("AQID"), Err(PemError::MalformedFraming));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_decode_contents_to() {
+        let contents: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        let input = encode(&Pem::new("BIG", contents.clone()));
+        let mut output = Vec::new();
+        let pem = parse_ref(&input).unwrap();
+        assert_eq!(pem.decode_contents_to(&mut output).unwrap(), 20_000);
+        assert_eq!(output, contents);
+
+        let refs = parse_many_ref(INPUT).unwrap();
+        let error = refs[1].decode_contents_to(io::sink()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        // Padding at the end of a batch with more data after it
+        let padded = format!(
+            "-----BEGIN FOO-----\n{}AA==\nAQID\n-----END FOO-----\n",
+            "A".repeat(4092)
+        );
+        let error = parse_ref(&padded)
+            .unwrap()
+            .decode_contents_to(io::sink())
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(parse(&padded).is_err());
+    }
+}
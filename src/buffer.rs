@@ -0,0 +1,155 @@
+use crate::{parse_captures, PemError, Result};
+use base64::engine::{general_purpose::STANDARD, Engine as _};
+use base64::DecodeSliceError;
+use core::{ops::Range, str};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+/// Where [`decode_into`] found the block and how much it decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedInfo {
+    /// The byte range of the tag in the input
+    pub tag: Range<usize>,
+    /// The number of bytes written to the output buffer
+    pub len: usize,
+}
+
+/// Parses a single PEM block and decodes its contents into `out`, without
+/// allocating.
+///
+/// Headers are skipped. Fails with [`PemError::BufferTooSmall`] if `out`
+/// cannot hold the decoded contents; only the error for mismatched tags
+/// allocates, to report both tags.
+///
+/// # Example
+/// ```rust
+///  use pem::decode_into;
+///
+///  let input = b"-----BEGIN FOO-----\nAQID\n-----END FOO-----\n";
+///  let mut out = [0; 16];
+///  let info = decode_into(input, &mut out).unwrap();
+///  assert_eq!(&input[info.tag], b"FOO");
+///  assert_eq!(&out[..info.len], [1, 2, 3]);
+/// ```
+pub fn decode_into(input: &[u8], out: &mut [u8]) -> Result<DecodedInfo> {
+    let caps = parse_captures(input).ok_or(PemError::MalformedFraming)?;
+    let tag = str::from_utf8(caps.begin).map_err(PemError::NotUtf8)?;
+    if tag.is_empty() {
+        return Err(PemError::MissingBeginTag);
+    }
+    let tag_end = str::from_utf8(caps.end).map_err(PemError::NotUtf8)?;
+    if tag_end.is_empty() {
+        return Err(PemError::MissingEndTag);
+    }
+    if tag != tag_end {
+        return Err(PemError::MismatchedTags(tag.into(), tag_end.into()));
+    }
+
+    // Strip whitespace a batch of whole base64 quanta at a time; padding
+    // can only occur in the last batch
+    let mut batch = [0; 256];
+    let mut batch_len = 0;
+    let mut len = 0;
+    for &c in caps.data.iter().filter(|c| !c.is_ascii_whitespace()) {
+        if batch_len == batch.len() {
+            len += decode_inner_batch(&batch, &mut out[len..])?;
+            batch_len = 0;
+        }
+        batch[batch_len] = c;
+        batch_len += 1;
+    }
+    len += decode_batch(&batch[..batch_len], &mut out[len..])?;
+
+    let start = caps.begin.as_ptr() as usize - input.as_ptr() as usize;
+    Ok(DecodedInfo {
+        tag: start..start + caps.begin.len(),
+        len,
+    })
+}
+
+/// Decode the base64 `data` of a block into `writer` a batch at a time,
+/// returning the number of bytes written
+#[cfg(feature = "std")]
+pub(crate) fn decode_data_to<W: Write>(data: &[u8], mut writer: W) -> io::Result<u64> {
+    let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+    let mut batch = [0; 4096];
+    let mut out = [0; 3072];
+    let mut batch_len = 0;
+    let mut len = 0;
+    for &c in data.iter().filter(|c| !c.is_ascii_whitespace()) {
+        if batch_len == batch.len() {
+            let n = decode_inner_batch(&batch, &mut out).map_err(invalid)?;
+            writer.write_all(&out[..n])?;
+            len += n as u64;
+            batch_len = 0;
+        }
+        batch[batch_len] = c;
+        batch_len += 1;
+    }
+    let n = decode_batch(&batch[..batch_len], &mut out).map_err(invalid)?;
+    writer.write_all(&out[..n])?;
+    Ok(len + n as u64)
+}
+
+/// Decode a batch that more data follows, so that it must not be padded
+fn decode_inner_batch(batch: &[u8], out: &mut [u8]) -> Result<usize> {
+    if batch.last() == Some(&b'=') {
+        return Err(PemError::InvalidData(base64::DecodeError::InvalidPadding));
+    }
+    decode_batch(batch, out)
+}
+
+fn decode_batch(batch: &[u8], out: &mut [u8]) -> Result<usize> {
+    STANDARD.decode_slice(batch, out).map_err(|e| match e {
+        DecodeSliceError::DecodeError(e) => PemError::InvalidData(e),
+        DecodeSliceError::OutputSliceTooSmall => PemError::BufferTooSmall,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode, parse, Pem};
+
+    #[test]
+    fn test_decode_into_matches_parse() {
+        let contents: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        let mut pem = Pem::new("BIG BLOCK", contents);
+        pem.headers_mut().add("Comment", "skipped").unwrap();
+        let input = format!("leading text\n{}", encode(&pem));
+
+        let mut out = [0; 1000];
+        let info = decode_into(input.as_bytes(), &mut out).unwrap();
+        assert_eq!(&input[info.tag], "BIG BLOCK");
+        assert_eq!(&out[..info.len], parse(&input).unwrap().contents());
+
+        assert_eq!(
+            decode_into(input.as_bytes(), &mut [0; 999]),
+            Err(PemError::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_decode_into_errors() {
+        let mut out = [0; 16];
+        assert!(matches!(
+            decode_into(b"-----BEGIN FOO-----\nA=ID\n-----END FOO-----\n", &mut out),
+            Err(PemError::InvalidData(_))
+        ));
+        assert_eq!(
+            decode_into(b"-----BEGIN FOO-----\nAQID\n-----END BAR-----\n", &mut out),
+            Err(PemError::MismatchedTags("FOO".into(), "BAR".into()))
+        );
+
+        // Padding ending the first batch of 256 characters, with more data
+        let input = format!(
+            "-----BEGIN FOO-----\n{}AA==AQID\n-----END FOO-----\n",
+            "A".repeat(252)
+        );
+        assert!(parse(&input).is_err());
+        assert_eq!(
+            decode_into(input.as_bytes(), &mut [0; 256]),
+            Err(PemError::InvalidData(base64::DecodeError::InvalidPadding))
+        );
+    }
+}
@@ -0,0 +1,450 @@
+use crate::{
+    encode_many, encode_many_config, parse_many, EncodeConfig, Pem, PemError, Result, TagMatcher,
+};
+use core::{ops::Deref, slice};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::{self, Vec},
+};
+#[cfg(any(feature = "std", test))]
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+};
+
+/// A bundle of blocks, such as a certificate chain or trust store
+///
+/// Dereferences to a slice of the blocks.
+///
+/// # Example
+/// ```rust
+///  use pem::{Pem, PemBundle};
+///
+///  let mut bundle: PemBundle = vec![
+///      Pem::new("CERTIFICATE", [1]),
+///      Pem::new("PRIVATE KEY", [2]),
+///      Pem::new("CERTIFICATE", [1]),
+///  ]
+///  .into();
+///  bundle.dedup();
+///  assert_eq!(bundle.len(), 2);
+///  assert_eq!(bundle.find_first("PRIVATE KEY").unwrap().contents(), [2]);
+///  assert_eq!(bundle.filter_by_tag("CERTIFICATE").count(), 1);
+///  assert_eq!(PemBundle::parse(bundle.encode()).unwrap(), bundle);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PemBundle(Vec<Pem>);
+
+impl PemBundle {
+    /// Create an empty bundle
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse all blocks of PEM-encoded data into a bundle
+    pub fn parse<B: AsRef<[u8]>>(input: B) -> Result<Self> {
+        parse_many(input).map(PemBundle)
+    }
+
+    /// Iterate over the blocks with the given tag
+    pub fn filter_by_tag<'a>(&'a self, tag: &'a str) -> FilterByTag<'a> {
+        FilterByTag {
+            iter: self.0.iter(),
+            tag,
+            matcher: None,
+        }
+    }
+
+    /// Iterate over the blocks whose tag matches `tag` according to
+    /// `matcher`
+    ///
+    /// # Example
+    /// ```rust
+    ///  use pem::{Pem, PemBundle, TagMatcher};
+    ///
+    ///  let bundle: PemBundle = vec![
+    ///      Pem::new("CERTIFICATE", [1]),
+    ///      Pem::new("X509 CERTIFICATE", [2]),
+    ///      Pem::new("TRUSTED CERTIFICATE", [3]),
+    ///  ]
+    ///  .into();
+    ///  let matcher = TagMatcher::standard();
+    ///  assert_eq!(bundle.filter_by_tag_with(&matcher, "CERTIFICATE").count(), 3);
+    /// ```
+    pub fn filter_by_tag_with<'a>(
+        &'a self,
+        matcher: &'a TagMatcher,
+        tag: &'a str,
+    ) -> FilterByTag<'a> {
+        FilterByTag {
+            iter: self.0.iter(),
+            tag,
+            matcher: Some(matcher),
+        }
+    }
+
+    /// Get the first block with the given tag
+    pub fn find_first(&self, tag: &str) -> Option<&Pem> {
+        self.0.iter().find(|pem| pem.tag() == tag)
+    }
+
+    /// Add a block to the end of the bundle
+    pub fn push(&mut self, pem: Pem) {
+        self.0.push(pem);
+    }
+
+    /// Move all blocks of `other` to the end of the bundle
+    pub fn append(&mut self, other: &mut PemBundle) {
+        self.0.append(&mut other.0);
+    }
+
+    /// Remove blocks whose binary contents equal those of an earlier block,
+    /// regardless of their tags and headers
+    pub fn dedup(&mut self) {
+        let mut seen = BTreeSet::new();
+        let keep: Vec<bool> = self
+            .0
+            .iter()
+            .map(|pem| seen.insert(pem.contents()))
+            .collect();
+        let mut keep = keep.into_iter();
+        self.0.retain(|_| keep.next().unwrap_or(true));
+    }
+
+    /// Encode the bundle, see [`encode_many`]
+    pub fn encode(&self) -> String {
+        encode_many(&self.0)
+    }
+
+    /// Encode the bundle with additional configuration options, see
+    /// [`encode_many_config`]
+    pub fn encode_config(&self, config: EncodeConfig) -> String {
+        encode_many_config(&self.0, config)
+    }
+
+    /// Consume the bundle to get the blocks
+    pub fn into_vec(self) -> Vec<Pem> {
+        self.0
+    }
+}
+
+impl Deref for PemBundle {
+    type Target = [Pem];
+
+    fn deref(&self) -> &[Pem] {
+        &self.0
+    }
+}
+
+impl From<Vec<Pem>> for PemBundle {
+    fn from(pems: Vec<Pem>) -> Self {
+        PemBundle(pems)
+    }
+}
+
+impl FromIterator<Pem> for PemBundle {
+    fn from_iter<I: IntoIterator<Item = Pem>>(iter: I) -> Self {
+        PemBundle(iter.into_iter().collect())
+    }
+}
+
+impl Extend<Pem> for PemBundle {
+    fn extend<I: IntoIterator<Item = Pem>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl IntoIterator for PemBundle {
+    type Item = Pem;
+    type IntoIter = vec::IntoIter<Pem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a PemBundle {
+    type Item = &'a Pem;
+    type IntoIter = slice::Iter<'a, Pem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Iterator over the blocks of a [`PemBundle`] with a given tag
+#[derive(Debug)]
+pub struct FilterByTag<'a> {
+    iter: slice::Iter<'a, Pem>,
+    tag: &'a str,
+    matcher: Option<&'a TagMatcher>,
+}
+
+impl<'a> Iterator for FilterByTag<'a> {
+    type Item = &'a Pem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (tag, matcher) = (self.tag, self.matcher);
+        self.iter.find(|pem| match matcher {
+            Some(matcher) => matcher.matches(pem.tag(), tag),
+            None => pem.tag() == tag,
+        })
+    }
+}
+
+/// How [`merge`] resolves duplicate blocks that only differ in their headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Fail with [`PemError::ConflictingDuplicate`]
+    Error,
+    /// Keep the block that was seen first
+    KeepFirst,
+    /// Keep the block that was seen last
+    KeepLast,
+    /// Keep the block that has headers, or the first one if both or
+    /// neither have headers
+    PreferHeaders,
+}
+
+/// Merge bundles into a single bundle without duplicate blocks.
+///
+/// Blocks are duplicates if they have the same tag and binary contents.
+/// Identical duplicates are always merged; duplicates whose headers differ
+/// are resolved according to `policy`. Each block of the result keeps the
+/// position at which it first appeared.
+///
+/// # Example
+/// ```rust
+///  use pem::{merge, MergePolicy, Pem};
+///
+///  let root = Pem::new("CERTIFICATE", [1, 2, 3]);
+///  let mut annotated = root.clone();
+///  annotated.headers_mut().add("Comment", "root").unwrap();
+///  let leaf = Pem::new("CERTIFICATE", [4, 5, 6]);
+///
+///  let first = [root.clone(), leaf.clone()];
+///  let second = [annotated.clone()];
+///  let merged = merge([&first[..], &second[..]], MergePolicy::PreferHeaders).unwrap();
+///  assert_eq!(merged, [annotated, leaf]);
+///
+///  assert!(merge([&first[..], &second[..]], MergePolicy::Error).is_err());
+/// ```
+pub fn merge<'a, I>(bundles: I, policy: MergePolicy) -> Result<Vec<Pem>>
+where
+    I: IntoIterator<Item = &'a [Pem]>,
+{
+    let mut merged: Vec<&'a Pem> = Vec::new();
+    let mut seen: BTreeMap<(&'a str, &'a [u8]), usize> = BTreeMap::new();
+
+    for pem in bundles.into_iter().flatten() {
+        let key = (pem.tag(), pem.contents());
+        let index = match seen.get(&key) {
+            Some(&index) => index,
+            None => {
+                seen.insert(key, merged.len());
+                merged.push(pem);
+                continue;
+            }
+        };
+
+        let kept = merged[index];
+        if kept.headers() == pem.headers() {
+            continue;
+        }
+        match policy {
+            MergePolicy::Error => {
+                return Err(PemError::ConflictingDuplicate(pem.tag().into()));
+            }
+            MergePolicy::KeepFirst => {}
+            MergePolicy::KeepLast => merged[index] = pem,
+            MergePolicy::PreferHeaders => {
+                if kept.headers().iter().next().is_none() {
+                    merged[index] = pem;
+                }
+            }
+        }
+    }
+
+    Ok(merged.into_iter().cloned().collect())
+}
+
+/// Tags of auxiliary blocks holding domain parameters, which [`group`]
+/// associates with a principal block
+pub const PARAMETER_TAGS: &[&str] = &[
+    "EC PARAMETERS",
+    "DH PARAMETERS",
+    "X9.42 DH PARAMETERS",
+    "DSA PARAMETERS",
+];
+
+/// A principal block along with its auxiliary parameter blocks, as
+/// returned by [`group`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Group {
+    /// The principal block, e.g. a key or certificate, or `None` if the
+    /// bundle only holds parameter blocks
+    pub principal: Option<Pem>,
+    /// The parameter blocks associated with the principal block, in input
+    /// order
+    pub parameters: Vec<Pem>,
+}
+
+/// Associate parameter blocks with the principal blocks they belong to.
+///
+/// Each block that is not listed in [`PARAMETER_TAGS`] starts a group.
+/// Parameter blocks join the group of the next principal block, as emitted
+/// e.g. by `openssl ecparam -genkey`; parameter blocks after the last
+/// principal block, like DH parameters appended to a certificate, join the
+/// group of the last principal block.
+///
+/// # Example
+/// ```rust
+///  use pem::{group, Pem};
+///
+///  let pems = vec![
+///      Pem::new("EC PARAMETERS", [6, 8, 42]),
+///      Pem::new("EC PRIVATE KEY", [1, 2, 3]),
+///      Pem::new("CERTIFICATE", [4, 5, 6]),
+///  ];
+///  let groups = group(pems);
+///  assert_eq!(groups.len(), 2);
+///  assert_eq!(groups[0].principal.as_ref().unwrap().tag(), "EC PRIVATE KEY");
+///  assert_eq!(groups[0].parameters[0].tag(), "EC PARAMETERS");
+///  assert!(groups[1].parameters.is_empty());
+/// ```
+pub fn group(pems: Vec<Pem>) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    let mut pending = Vec::new();
+    for pem in pems {
+        if PARAMETER_TAGS.contains(&pem.tag()) {
+            pending.push(pem);
+        } else {
+            groups.push(Group {
+                principal: Some(pem),
+                parameters: core::mem::take(&mut pending),
+            });
+        }
+    }
+    if !pending.is_empty() {
+        match groups.last_mut() {
+            Some(last) => last.parameters.append(&mut pending),
+            None => groups.push(Group {
+                principal: None,
+                parameters: pending,
+            }),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn with_comment(comment: &str) -> Pem {
+        let mut pem = Pem::new("CERTIFICATE", [1, 2, 3]);
+        pem.headers_mut().add("Comment", comment).unwrap();
+        pem
+    }
+
+    #[test]
+    fn test_merge_policies() {
+        let plain = Pem::new("CERTIFICATE", [1, 2, 3]);
+        let other = Pem::new("CERTIFICATE", [4, 5, 6]);
+        let first = [plain.clone(), with_comment("a"), other.clone()];
+        let second = [with_comment("b"), other.clone()];
+        let bundles = || [&first[..], &second[..]];
+
+        assert_eq!(
+            merge(bundles(), MergePolicy::KeepFirst).unwrap(),
+            [plain.clone(), other.clone()]
+        );
+        assert_eq!(
+            merge(bundles(), MergePolicy::KeepLast).unwrap(),
+            [with_comment("b"), other.clone()]
+        );
+        assert_eq!(
+            merge(bundles(), MergePolicy::PreferHeaders).unwrap(),
+            [with_comment("a"), other.clone()]
+        );
+        assert_eq!(
+            merge(bundles(), MergePolicy::Error),
+            Err(PemError::ConflictingDuplicate("CERTIFICATE".into()))
+        );
+    }
+
+    #[test]
+    fn test_group_trailing_parameters() {
+        let cert = Pem::new("CERTIFICATE", [1]);
+        let key = Pem::new("PRIVATE KEY", [2]);
+        let dh = Pem::new("DH PARAMETERS", [3]);
+        let groups = group(vec![cert.clone(), key.clone(), dh.clone()]);
+        assert_eq!(
+            groups,
+            [
+                Group {
+                    principal: Some(cert),
+                    parameters: vec![],
+                },
+                Group {
+                    principal: Some(key),
+                    parameters: vec![dh.clone()],
+                },
+            ]
+        );
+
+        assert_eq!(
+            group(vec![dh.clone()]),
+            [Group {
+                principal: None,
+                parameters: vec![dh],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merge_identical_duplicates() {
+        let bundle = [with_comment("a"), Pem::new("PRIVATE KEY", [1, 2, 3])];
+        assert_eq!(
+            merge([&bundle[..], &bundle[..]], MergePolicy::Error).unwrap(),
+            bundle
+        );
+    }
+
+    #[test]
+    fn test_bundle_operations() {
+        let mut bundle = PemBundle::new();
+        bundle.push(with_comment("a"));
+        bundle.push(Pem::new("PRIVATE KEY", [4, 5, 6]));
+        let mut other: PemBundle = [with_comment("b"), Pem::new("CERTIFICATE", [7])]
+            .into_iter()
+            .collect();
+        bundle.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(bundle.len(), 4);
+
+        bundle.dedup();
+        assert_eq!(
+            bundle.into_vec(),
+            [
+                with_comment("a"),
+                Pem::new("PRIVATE KEY", [4, 5, 6]),
+                Pem::new("CERTIFICATE", [7])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bundle_as_map_key() {
+        let bundle: PemBundle = [with_comment("a"), Pem::new("PRIVATE KEY", [4])]
+            .into_iter()
+            .collect();
+        let mut seen = std::collections::HashSet::new();
+        assert!(seen.insert(bundle.clone()));
+        assert!(!seen.insert(PemBundle::parse(bundle.encode()).unwrap()));
+        assert!(seen.insert(PemBundle::new()));
+    }
+}
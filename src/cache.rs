@@ -0,0 +1,174 @@
+use crate::{parse_many, Pem};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+    time::SystemTime,
+};
+
+/// A cached parse result, along with what identifies the file version
+#[derive(Debug)]
+struct Entry {
+    modified: SystemTime,
+    len: u64,
+    pems: Arc<[Pem]>,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: HashMap<PathBuf, Entry>,
+    clock: u64,
+}
+
+/// Memoizes the parsed contents of PEM files
+///
+/// A cached result is reused as long as the modification time and size of
+/// the file are unchanged. Once more than `capacity` files are cached, the
+/// least recently used entry is evicted. The cache can be shared between
+/// threads.
+///
+/// # Example
+/// ```rust,no_run
+///  use pem::PemCache;
+///
+///  let cache = PemCache::new(16);
+///  // Only parsed again if the bundle changed on disk
+///  let roots = cache.get("/etc/ssl/certs/ca-certificates.crt")?;
+///  # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct PemCache {
+    capacity: usize,
+    state: Mutex<State>,
+}
+
+impl PemCache {
+    /// Create a new cache holding the results of at most `capacity` files
+    pub fn new(capacity: usize) -> Self {
+        PemCache {
+            capacity,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Get the parsed blocks of the file at `path`, parsing it only if it
+    /// is not cached or changed since it was cached
+    pub fn get(&self, path: impl AsRef<Path>) -> io::Result<Arc<[Pem]>> {
+        let path = path.as_ref();
+        let metadata = fs::metadata(path)?;
+        let (modified, len) = (metadata.modified()?, metadata.len());
+
+        let mut state = self.lock();
+        state.clock += 1;
+        let clock = state.clock;
+        if let Some(entry) = state.entries.get_mut(path) {
+            if entry.modified == modified && entry.len == len {
+                entry.last_used = clock;
+                return Ok(Arc::clone(&entry.pems));
+            }
+        }
+        // Don't hold the lock while reading and parsing
+        drop(state);
+
+        let pems: Arc<[Pem]> = parse_many(fs::read(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+            .into();
+
+        let mut state = self.lock();
+        if !state.entries.contains_key(path) && state.entries.len() >= self.capacity {
+            let lru = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(path, _)| path.clone());
+            if let Some(lru) = lru {
+                state.entries.remove(&lru);
+            }
+        }
+        if self.capacity > 0 {
+            let entry = Entry {
+                modified,
+                len,
+                pems: Arc::clone(&pems),
+                last_used: clock,
+            };
+            state.entries.insert(path.to_path_buf(), entry);
+        }
+        Ok(pems)
+    }
+
+    /// Drop the cached result for the file at `path`
+    pub fn invalidate(&self, path: impl AsRef<Path>) {
+        self.lock().entries.remove(path.as_ref());
+    }
+
+    /// Drop all cached results
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Get the number of cached files
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether no files are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encode_many;
+
+    fn temp_file(name: &str, pems: &[Pem]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("pem-cache-{}-{name}.pem", std::process::id()));
+        fs::write(&path, encode_many(pems)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_reuses_unchanged_files() {
+        let cache = PemCache::new(4);
+        let path = temp_file("unchanged", &[Pem::new("FOO", [1, 2, 3])]);
+        let first = cache.get(&path).unwrap();
+        let second = cache.get(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let changed = [Pem::new("FOO", [1, 2, 3]), Pem::new("BAR", [4, 5, 6])];
+        fs::write(&path, encode_many(&changed)).unwrap();
+        let third = cache.get(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(&*third, &changed);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = PemCache::new(2);
+        let paths: Vec<PathBuf> = (0..3)
+            .map(|i| temp_file(&format!("lru-{i}"), &[Pem::new("FOO", [i])]))
+            .collect();
+        let first = cache.get(&paths[0]).unwrap();
+        cache.get(&paths[1]).unwrap();
+        cache.get(&paths[0]).unwrap();
+        cache.get(&paths[2]).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        // paths[1] was evicted, paths[0] was not
+        assert!(Arc::ptr_eq(&first, &cache.get(&paths[0]).unwrap()));
+        cache.invalidate(&paths[0]);
+        assert_eq!(cache.len(), 1);
+        for path in paths {
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Build-time generation of Rust constants from PEM files.
+//!
+//! [`embed`] parses PEM files and writes a Rust source file holding the
+//! decoded contents of every block as a `pub const` byte slice, along with
+//! its tag. Including that file embeds e.g. pinned root certificates into a
+//! binary without parsing them at runtime.
+//!
+//! For a file named `isrg-root-x1.pem` holding a single certificate, the
+//! generated code looks like:
+//!
+//! ```rust
+//! pub const ISRG_ROOT_X1_TAG: &str = "CERTIFICATE";
+//! pub const ISRG_ROOT_X1: &[u8] = &[
+//!     0x30, 0x82, 0x05, 0x6b, // ...
+//! ];
+//! ```
+//!
+//! Files holding several blocks get one pair of constants per block, with
+//! the index of the block appended to the name (`BUNDLE_0`, `BUNDLE_1`, ...).
+//! Files whose names would give the same constant, such as `a/root.pem` and
+//! `b/root.pem` or `root-ca.pem` and `root_ca.pem`, are rejected.
+//!
+//! # Example: `build.rs`
+//! ```rust,no_run
+//!  use std::{env, path::Path};
+//!
+//!  fn main() -> std::io::Result<()> {
+//!      let out = Path::new(&env::var("OUT_DIR").unwrap()).join("roots.rs");
+//!      pem::codegen::embed(["roots/isrg-root-x1.pem"], out)?;
+//!      println!("cargo:rerun-if-changed=roots/isrg-root-x1.pem");
+//!      Ok(())
+//!  }
+//! ```
+//!
+//! and then, in the crate itself:
+//!
+//! ```rust,ignore
+//! include!(concat!(env!("OUT_DIR"), "/roots.rs"));
+//! ```
+use crate::{parse_many, Pem};
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Parse the PEM files at `paths` and write Rust constants holding their
+/// blocks to `out`
+///
+/// Fails with an error of kind `InvalidInput` naming both files if two
+/// files would generate a constant of the same name.
+pub fn embed<I, P>(paths: I, out: impl AsRef<Path>) -> io::Result<()>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut code = String::from("// @generated by pem::codegen, do not edit\n");
+    let mut generated: HashMap<String, PathBuf> = HashMap::new();
+    for path in paths {
+        let path = path.as_ref();
+        let pems = parse_many(fs::read(path)?).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })?;
+        let name = const_name(path);
+        for i in 0..pems.len() {
+            let name = block_name(&name, i, pems.len());
+            for name in [format!("{name}_TAG"), name] {
+                if let Some(other) = generated.insert(name.clone(), path.to_path_buf()) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "{} and {} both generate the constant {}",
+                            other.display(),
+                            path.display(),
+                            name
+                        ),
+                    ));
+                }
+            }
+        }
+        render(&mut code, &name, &pems);
+    }
+    fs::write(out, code)
+}
+
+fn render(code: &mut String, name: &str, pems: &[Pem]) {
+    for (i, pem) in pems.iter().enumerate() {
+        let name = block_name(name, i, pems.len());
+        writeln!(code).unwrap();
+        writeln!(code, "pub const {name}_TAG: &str = {:?};", pem.tag()).unwrap();
+        writeln!(code, "pub const {name}: &[u8] = &[").unwrap();
+        for chunk in pem.contents().chunks(16) {
+            code.push_str("   ");
+            for byte in chunk {
+                write!(code, " {byte:#04x},").unwrap();
+            }
+            code.push('\n');
+        }
+        code.push_str("];\n");
+    }
+}
+
+/// The name of the constants for block `i` of `len` blocks of a file
+fn block_name(name: &str, i: usize, len: usize) -> String {
+    if len == 1 {
+        name.to_string()
+    } else {
+        format!("{name}_{i}")
+    }
+}
+
+/// Turn the file stem of `path` into a SCREAMING_SNAKE_CASE identifier
+fn const_name(path: &Path) -> String {
+    let mut name: String = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_const_name() {
+        assert_eq!(
+            const_name(Path::new("certs/isrg-root.x1.pem")),
+            "ISRG_ROOT_X1"
+        );
+        assert_eq!(const_name(Path::new("2024 root.crt")), "_2024_ROOT");
+    }
+
+    #[test]
+    fn test_render() {
+        let mut code = String::new();
+        let pems = [
+            Pem::new("CERTIFICATE", (0..17).collect::<Vec<u8>>()),
+            Pem::new("PRIVATE KEY", []),
+        ];
+        render(&mut code, "BUNDLE", &pems);
+        assert_eq!(
+            code,
+            r#"
+pub const BUNDLE_0_TAG: &str = "CERTIFICATE";
+pub const BUNDLE_0: &[u8] = &[
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+    0x10,
+];
+
+pub const BUNDLE_1_TAG: &str = "PRIVATE KEY";
+pub const BUNDLE_1: &[u8] = &[
+];
+"#
+        );
+    }
+
+    #[test]
+    fn test_embed_reports_path() {
+        let dir = std::env::temp_dir();
+        let input = dir.join(format!("pem-codegen-{}.pem", std::process::id()));
+        fs::write(&input, "-----BEGIN FOO-----\nAQ?D\n-----END FOO-----\n").unwrap();
+        let err = embed([&input], dir.join("unused.rs")).unwrap_err();
+        fs::remove_file(&input).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with(&input.display().to_string()));
+    }
+
+    #[test]
+    fn test_embed_rejects_duplicate_names() {
+        let dir = std::env::temp_dir().join(format!("pem-codegen-dup-{}", std::process::id()));
+        fs::create_dir_all(dir.join("a")).unwrap();
+        fs::create_dir_all(dir.join("b")).unwrap();
+        let block = crate::encode(&Pem::new("CERTIFICATE", [1]));
+        let bundle = crate::encode_many(&[Pem::new("A", [1]), Pem::new("B", [2])]);
+        for (name, contents) in [
+            ("a/root.pem", &block),
+            ("b/root.pem", &block),
+            ("root-ca.pem", &block),
+            ("root_ca.pem", &block),
+            ("bundle.pem", &bundle),
+            ("bundle-1.pem", &block),
+        ] {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+        let out = dir.join("out.rs");
+        let results = [
+            embed([dir.join("a/root.pem"), dir.join("b/root.pem")], &out),
+            embed([dir.join("root-ca.pem"), dir.join("root_ca.pem")], &out),
+            embed([dir.join("bundle.pem"), dir.join("bundle-1.pem")], &out),
+            embed([dir.join("a/root.pem"), dir.join("root-ca.pem")], &out),
+        ];
+        fs::remove_dir_all(&dir).unwrap();
+
+        let [same_stem, same_name, same_index, distinct] = results;
+        for (err, first, second, name) in [
+            (same_stem, "a/root.pem", "b/root.pem", "ROOT"),
+            (same_name, "root-ca.pem", "root_ca.pem", "ROOT_CA"),
+            (same_index, "bundle.pem", "bundle-1.pem", "BUNDLE_1"),
+        ] {
+            let err = err.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "{} and {} both generate the constant {}_TAG",
+                    dir.join(first).display(),
+                    dir.join(second).display(),
+                    name
+                )
+            );
+        }
+        distinct.unwrap();
+    }
+}
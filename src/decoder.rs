@@ -0,0 +1,252 @@
+use crate::parser::find;
+use crate::{parse_captures_iter, Pem, PemError, Result};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::vec::Vec;
+
+/// The default limit for the buffered size of a single block
+pub(crate) const DEFAULT_MAX_BLOCK_LEN: usize = 16 * 1024 * 1024;
+
+const BEGIN: &[u8] = b"-----BEGIN ";
+
+/// The markers following BEGIN that complete a block, as searched for by
+/// the parser: the end of the BEGIN line, the END marker and the end of
+/// the END line
+const MARKERS: [&[u8]; 3] = [b"-----", b"-----END ", b"-----"];
+
+/// An event produced by [`PemDecoder`]
+#[derive(Debug, PartialEq)]
+pub enum Event {
+    /// A block was read completely and decoded
+    Block(Pem),
+    /// A block was read completely but failed to decode, or exceeded the
+    /// size limit
+    Error(PemError),
+}
+
+/// Incremental push parser for PEM-encoded data, independent of any IO
+///
+/// The caller feeds the data in chunks of any size, as it arrives from a
+/// socket, a DMA buffer or an async stream, and gets back the events for
+/// the blocks completed by each chunk. Only the block being read is
+/// buffered, up to a configurable limit. Like
+/// [`parse_many`](crate::parse_many), text between blocks is ignored.
+///
+/// # Example
+/// ```rust
+///  use pem::{Event, PemDecoder};
+///
+///  let mut decoder = PemDecoder::new();
+///  assert!(decoder.feed(b"-----BEGIN FOO-----\nAQ").is_empty());
+///  match &decoder.feed(b"ID\n-----END FOO-----\n")[..] {
+///      [Event::Block(pem)] => assert_eq!(pem.contents(), [1, 2, 3]),
+///      events => panic!("unexpected events: {events:?}"),
+///  }
+///  decoder.finish().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct PemDecoder {
+    buf: Vec<u8>,
+    max_block_len: usize,
+    /// How many of the `MARKERS` of the buffered block were found
+    markers: usize,
+    /// Where to resume searching for the next marker, so each byte is only
+    /// searched once however the input is chunked
+    searched: usize,
+}
+
+impl Default for PemDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PemDecoder {
+    /// Create a new decoder
+    pub fn new() -> Self {
+        PemDecoder {
+            buf: Vec::new(),
+            max_block_len: DEFAULT_MAX_BLOCK_LEN,
+            markers: 0,
+            searched: BEGIN.len(),
+        }
+    }
+
+    /// Set the maximum number of bytes buffered for a single block, 16 MiB
+    /// by default. A block exceeding it is reported as
+    /// [`PemError::TooLarge`] and skipped.
+    pub fn set_max_block_len(mut self, max_block_len: usize) -> Self {
+        self.max_block_len = max_block_len;
+        self
+    }
+
+    /// Feed the next chunk of input, returning the events for the blocks it
+    /// completes, in input order
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<Event> {
+        self.buf.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        loop {
+            self.skip_to_begin();
+            if !self.find_markers() {
+                break;
+            }
+            // The parser is only run on complete blocks, so that blocks fed
+            // in many chunks are not parsed over and over
+            let (span, caps) = match parse_captures_iter(&self.buf).next_spanned() {
+                Some(matched) => matched,
+                None => break,
+            };
+            events.push(if span.len() > self.max_block_len {
+                Event::Error(PemError::TooLarge(self.max_block_len))
+            } else {
+                match Pem::new_from_captures(caps) {
+                    Ok(pem) => Event::Block(pem),
+                    Err(e) => Event::Error(e),
+                }
+            });
+            self.buf.drain(..span.end);
+            self.reset();
+        }
+
+        if self.buf.len() > self.max_block_len {
+            // Skip the rest of the block, which no longer starts with a
+            // BEGIN marker
+            self.buf.clear();
+            self.reset();
+            events.push(Event::Error(PemError::TooLarge(self.max_block_len)));
+        }
+        events
+    }
+
+    /// Drop what can't be part of a block, keeping a possibly incomplete
+    /// BEGIN marker at the end of the buffer
+    fn skip_to_begin(&mut self) {
+        if self.buf.starts_with(BEGIN) {
+            return;
+        }
+        let keep_from = find(&self.buf, BEGIN)
+            .unwrap_or_else(|| self.buf.len().saturating_sub(BEGIN.len() - 1));
+        self.buf.drain(..keep_from);
+        self.reset();
+    }
+
+    /// Whether the buffer starts with a complete block, searching for its
+    /// markers from where the previous call stopped
+    fn find_markers(&mut self) -> bool {
+        if !self.buf.starts_with(BEGIN) {
+            return false;
+        }
+        while let Some(marker) = MARKERS.get(self.markers) {
+            match find(&self.buf[self.searched..], marker) {
+                Some(pos) => {
+                    self.searched += pos + marker.len();
+                    self.markers += 1;
+                }
+                None => {
+                    // Keep the tail, which may hold the start of the marker
+                    let tail = self.buf.len().saturating_sub(marker.len() - 1);
+                    self.searched = self.searched.max(tail);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        self.markers = 0;
+        self.searched = BEGIN.len();
+    }
+
+    /// Signal the end of the input, failing with
+    /// [`PemError::MissingEndTag`] if a block was left incomplete
+    pub fn finish(self) -> Result<()> {
+        match find(&self.buf, BEGIN) {
+            Some(_) => Err(PemError::MissingEndTag),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode, parse_many};
+
+    #[test]
+    fn test_decoder_matches_parse_many() {
+        let input: String = (0..20u8)
+            .map(|i| format!("text {i}\n{}", encode(&Pem::new("BLOCK", vec![i; 100]))))
+            .collect();
+        for size in [1, 3, 64, input.len()] {
+            let mut decoder = PemDecoder::new();
+            let mut pems = Vec::new();
+            for chunk in input.as_bytes().chunks(size) {
+                for event in decoder.feed(chunk) {
+                    match event {
+                        Event::Block(pem) => pems.push(pem),
+                        Event::Error(e) => panic!("unexpected error: {e}"),
+                    }
+                }
+            }
+            decoder.finish().unwrap();
+            assert_eq!(pems, parse_many(&input).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_decoder_errors() {
+        let mut decoder = PemDecoder::new();
+        let events = decoder.feed(
+            b"-----BEGIN FOO-----\nAQID\n-----END BAR-----\n\
+              -----BEGIN FOO-----\nAQID\n-----END FOO-----\n",
+        );
+        assert_eq!(
+            events,
+            [
+                Event::Error(PemError::MismatchedTags("FOO".into(), "BAR".into())),
+                Event::Block(Pem::new("FOO", [1, 2, 3])),
+            ]
+        );
+        assert!(decoder.feed(b"-----BEGIN FOO-----\nAQID\n").is_empty());
+        assert_eq!(decoder.finish(), Err(PemError::MissingEndTag));
+
+        let mut decoder = PemDecoder::new().set_max_block_len(100);
+        let input = encode(&Pem::new("FOO", vec![0; 1000]));
+        let events: Vec<Event> = input
+            .as_bytes()
+            .chunks(10)
+            .flat_map(|chunk| decoder.feed(chunk))
+            .collect();
+        assert_eq!(events, [Event::Error(PemError::TooLarge(100))]);
+        assert_eq!(
+            decoder.feed(encode(&Pem::new("BAR", [1])).as_bytes()),
+            [Event::Block(Pem::new("BAR", [1]))]
+        );
+        decoder.finish().unwrap();
+    }
+
+    #[test]
+    fn test_decoder_small_chunks() {
+        // Each byte is only searched once, so feeding a large block a byte
+        // or a line at a time stays fast
+        let pem = Pem::new("BIG", vec![7; 3 * 1024 * 1024]);
+        let input = format!("text\n{}", encode(&pem));
+        let mut decoder = PemDecoder::new();
+        let events: Vec<Event> = input
+            .as_bytes()
+            .chunks(1)
+            .flat_map(|chunk| decoder.feed(chunk))
+            .collect();
+        assert_eq!(events, [Event::Block(pem.clone())]);
+
+        let mut decoder = PemDecoder::new();
+        let events: Vec<Event> = input
+            .split_inclusive('\n')
+            .flat_map(|line| decoder.feed(line.as_bytes()))
+            .collect();
+        assert_eq!(events, [Event::Block(pem)]);
+        decoder.finish().unwrap();
+    }
+}
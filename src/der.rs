@@ -0,0 +1,132 @@
+//! Just enough DER to walk and re-wrap the structures in PEM blocks.
+// Each feature using this module only needs some of it
+#![cfg_attr(
+    not(all(feature = "pkcs7", feature = "pkcs8", feature = "x509")),
+    allow(dead_code)
+)]
+
+use core::fmt::Write as _;
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{string::String, vec, vec::Vec};
+
+pub const INTEGER: u8 = 0x02;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OID: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+pub const SET: u8 = 0x31;
+pub const CONTEXT_0: u8 = 0xa0;
+pub const CONTEXT_1: u8 = 0xa1;
+
+/// A single tag-length-value element
+#[derive(Debug, Clone, Copy)]
+pub struct Tlv<'a> {
+    pub tag: u8,
+    pub value: &'a [u8],
+    /// The whole encoding, including tag and length
+    pub raw: &'a [u8],
+}
+
+/// Read one element from the start of `input`, returning it and the rest of
+/// the input. Multi-byte tags and indefinite lengths are not DER and are
+/// rejected.
+pub fn read(input: &[u8]) -> Option<(Tlv<'_>, &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    if tag & 0x1f == 0x1f {
+        return None;
+    }
+    let (&first, mut rest) = rest.split_first()?;
+    let len = if first < 0x80 {
+        usize::from(first)
+    } else {
+        let count = usize::from(first & 0x7f);
+        if count == 0 || count > (usize::BITS / 8) as usize || rest.len() < count {
+            return None;
+        }
+        let (bytes, after) = rest.split_at(count);
+        rest = after;
+        bytes
+            .iter()
+            .fold(0usize, |len, &b| (len << 8) | usize::from(b))
+    };
+    if rest.len() < len {
+        return None;
+    }
+    let header = input.len() - rest.len();
+    let tlv = Tlv {
+        tag,
+        value: &rest[..len],
+        raw: &input[..header + len],
+    };
+    Some((tlv, &rest[len..]))
+}
+
+/// Read one element with the expected tag
+pub fn expect(input: &[u8], tag: u8) -> Option<(Tlv<'_>, &[u8])> {
+    read(input).filter(|(tlv, _)| tlv.tag == tag)
+}
+
+/// Iterate over the elements of a constructed value
+pub fn children(mut input: &[u8]) -> impl Iterator<Item = Option<Tlv<'_>>> {
+    core::iter::from_fn(move || {
+        if input.is_empty() {
+            return None;
+        }
+        match read(input) {
+            Some((tlv, rest)) => {
+                input = rest;
+                Some(Some(tlv))
+            }
+            None => {
+                input = &[];
+                Some(None)
+            }
+        }
+    })
+}
+
+/// Encode one element
+pub fn write(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    if value.len() < 0x80 {
+        out.push(value.len() as u8);
+    } else {
+        let bytes = value.len().to_be_bytes();
+        let skip = bytes.iter().take_while(|&&b| b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(value);
+    out
+}
+
+/// Format an object identifier in dotted notation, e.g. `1.2.840.113549`
+pub fn oid_to_string(oid: &[u8]) -> String {
+    let mut arcs = Vec::new();
+    let mut arc = 0u64;
+    for &b in oid {
+        arc = (arc << 7) | u64::from(b & 0x7f);
+        if b & 0x80 == 0 {
+            arcs.push(arc);
+            arc = 0;
+        }
+    }
+    let mut dotted = String::new();
+    for (i, arc) in arcs.into_iter().enumerate() {
+        if i == 0 {
+            let first = (arc / 40).min(2);
+            let _ = write!(dotted, "{}.{}", first, arc - first * 40);
+        } else {
+            let _ = write!(dotted, ".{arc}");
+        }
+    }
+    dotted
+}
+
+#[cfg(test)]
+pub mod build {
+    //! Helpers for building DER test inputs
+
+    pub use super::write as tlv;
+}
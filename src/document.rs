@@ -0,0 +1,238 @@
+//! A lossless model of PEM files, keeping the explanatory text that RFC
+//! 7468 allows around blocks.
+//!
+//! ```rust
+//!  use pem::document::{Document, Segment};
+//!
+//!  let input = "Subject: CN=example\n\
+//!               -----BEGIN CERTIFICATE-----\n\
+//!               AQID\n\
+//!               -----END CERTIFICATE-----\n\
+//!               Subject: CN=root\n\
+//!               -----BEGIN CERTIFICATE-----\n\
+//!               BAUG\n\
+//!               -----END CERTIFICATE-----\n";
+//!  let mut document = Document::parse(input).unwrap();
+//!  assert_eq!(document.to_string(), input);
+//!  assert!(matches!(&document.segments()[0], Segment::Text(text) if text == "Subject: CN=example\n"));
+//!
+//!  document.blocks_mut().nth(1).unwrap().pem_mut().set_contents([7, 8, 9]);
+//!  assert_eq!(document.to_string(), input.replace("BAUG", "BwgJ"));
+//! ```
+use crate::{encode_config, parse_captures_iter, EncodeConfig, LineEnding, Pem, Result};
+use core::{fmt, slice};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// A part of a [`Document`]
+#[derive(Debug, Clone)]
+pub enum Segment {
+    /// Text outside of blocks, including the line breaks after blocks
+    Text(String),
+    /// A PEM block
+    Block(Block),
+}
+
+/// A block of a [`Document`], along with its original text
+#[derive(Debug, Clone)]
+pub struct Block {
+    pem: Pem,
+    /// The original text, until the block is modified
+    raw: Option<String>,
+    line_ending: LineEnding,
+}
+
+impl Block {
+    /// Get the parsed block
+    pub fn pem(&self) -> &Pem {
+        &self.pem
+    }
+
+    /// Get the block for modification, after which it is re-encoded when
+    /// the document is written, keeping its original line ending
+    pub fn pem_mut(&mut self) -> &mut Pem {
+        self.raw = None;
+        &mut self.pem
+    }
+
+    /// Get the original text of the block, unless it has been modified
+    pub fn raw(&self) -> Option<&str> {
+        self.raw.as_deref()
+    }
+}
+
+/// A PEM file as an ordered sequence of text and blocks
+///
+/// Writing the document with its `Display` implementation reproduces the
+/// input byte for byte, except for blocks that have been modified, which
+/// are re-encoded.
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    segments: Vec<Segment>,
+}
+
+impl Document {
+    /// Parse a file into its text and blocks, failing on the first invalid
+    /// block
+    pub fn parse(input: &str) -> Result<Document> {
+        let mut segments = Vec::new();
+        let mut matches = parse_captures_iter(input.as_bytes());
+        let mut text_start = 0;
+        while let Some((span, caps)) = matches.next_spanned() {
+            if span.start > text_start {
+                segments.push(Segment::Text(input[text_start..span.start].to_string()));
+            }
+            let raw = &input[span.clone()];
+            let line_ending = if raw.contains("\r\n") {
+                LineEnding::CRLF
+            } else {
+                LineEnding::LF
+            };
+            segments.push(Segment::Block(Block {
+                pem: Pem::new_from_captures(caps)?,
+                raw: Some(raw.to_string()),
+                line_ending,
+            }));
+            text_start = span.end;
+        }
+        if text_start < input.len() {
+            segments.push(Segment::Text(input[text_start..].to_string()));
+        }
+        Ok(Document { segments })
+    }
+
+    /// Get the segments in input order
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Get the segments for modification, e.g. to insert or remove text
+    /// or blocks
+    pub fn segments_mut(&mut self) -> &mut Vec<Segment> {
+        &mut self.segments
+    }
+
+    /// Iterate over the blocks
+    pub fn blocks(&self) -> Blocks<'_> {
+        Blocks(self.segments.iter())
+    }
+
+    /// Iterate over the blocks for modification
+    pub fn blocks_mut(&mut self) -> BlocksMut<'_> {
+        BlocksMut(self.segments.iter_mut())
+    }
+}
+
+/// Iterator over the blocks of a [`Document`]
+#[derive(Debug)]
+pub struct Blocks<'a>(slice::Iter<'a, Segment>);
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = &'a Block;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.find_map(|segment| match segment {
+            Segment::Block(block) => Some(block),
+            Segment::Text(_) => None,
+        })
+    }
+}
+
+/// Iterator over the blocks of a [`Document`] for modification
+#[derive(Debug)]
+pub struct BlocksMut<'a>(slice::IterMut<'a, Segment>);
+
+impl<'a> Iterator for BlocksMut<'a> {
+    type Item = &'a mut Block;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.find_map(|segment| match segment {
+            Segment::Block(block) => Some(block),
+            Segment::Text(_) => None,
+        })
+    }
+}
+
+impl From<Pem> for Segment {
+    fn from(pem: Pem) -> Self {
+        Segment::Block(Block {
+            pem,
+            raw: None,
+            line_ending: LineEnding::LF,
+        })
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => f.write_str(text)?,
+                Segment::Block(Block { raw: Some(raw), .. }) => f.write_str(raw)?,
+                Segment::Block(block) => {
+                    // Like the original text, stop at the END boundary; the
+                    // line break belongs to the following text
+                    let config = EncodeConfig::new()
+                        .set_line_ending(block.line_ending)
+                        .set_trailing_newline(false);
+                    f.write_str(&encode_config(&block.pem, config))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_preserves_formatting() {
+        let input = "Certificate:\r\n    Data: ...\r\n\
+                     -----BEGIN CERTIFICATE-----\r\n\
+                     Comment: kept\r\n\
+                     \r\n\
+                     AQ\r\n  ID\r\n\
+                     -----END CERTIFICATE-----   \r\n\r\n\
+                     -----BEGIN CERTIFICATE-----\r\nBAUG\r\n-----END CERTIFICATE-----";
+        let mut document = Document::parse(input).unwrap();
+        assert_eq!(document.to_string(), input);
+        assert_eq!(document.segments().len(), 4);
+        assert_eq!(
+            document.blocks().next().unwrap().pem().contents(),
+            [1, 2, 3]
+        );
+
+        let last = document.blocks_mut().last().unwrap();
+        last.pem_mut().set_tag("X509 CRL");
+        assert!(last.raw().is_none());
+        assert_eq!(
+            document.to_string(),
+            input.replace(
+                "-----BEGIN CERTIFICATE-----\r\nBAUG\r\n-----END CERTIFICATE-----",
+                "-----BEGIN X509 CRL-----\r\nBAUG\r\n-----END X509 CRL-----"
+            )
+        );
+
+        document.segments_mut().push(Segment::Text("\n".into()));
+        document.segments_mut().push(Pem::new("FOO", [1]).into());
+        assert!(document
+            .to_string()
+            .ends_with("\n-----BEGIN FOO-----\nAQ==\n-----END FOO-----"));
+    }
+
+    #[test]
+    fn test_invalid_block() {
+        let input = "text\n-----BEGIN FOO-----\nAQID\n-----END BAR-----\n";
+        assert!(Document::parse(input).is_err());
+        assert_eq!(
+            Document::parse("just text").unwrap().to_string(),
+            "just text"
+        );
+    }
+}
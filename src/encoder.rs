@@ -0,0 +1,167 @@
+use crate::{write_pem, EncodeConfig, IoWriter, Pem};
+use core::fmt::{self, Write as _};
+use std::io::{self, BufWriter, Write};
+
+/// Incremental encoder writing blocks to an `io::Write` as they come
+///
+/// Blocks written in a row are separated like
+/// [`encode_many_config`](crate::encode_many_config) separates them, so
+/// writing a list of blocks one at a time produces the same output.
+/// Explanatory text can be written between blocks. The output is buffered;
+/// call [`Encoder::flush`] to push out what has been written so far, and
+/// [`Encoder::finish`] when done, which also writes the trailing newline.
+///
+/// # Example
+/// ```rust
+///  use pem::{EncodeConfig, Encoder, LineEnding, Pem};
+///
+///  let config = EncodeConfig::new().set_line_ending(LineEnding::LF);
+///  let mut encoder = Encoder::new(Vec::new()).set_config(config);
+///  encoder.write_text("Leaf certificate:")?;
+///  encoder.write_block(&Pem::new("CERTIFICATE", [1, 2, 3]))?;
+///  encoder.write_block(&Pem::new("CERTIFICATE", [4, 5, 6]))?;
+///  let output = encoder.finish()?;
+///  assert_eq!(
+///      String::from_utf8(output).unwrap(),
+///      "Leaf certificate:\n\
+///       -----BEGIN CERTIFICATE-----\nAQID\n-----END CERTIFICATE-----\n\n\
+///       -----BEGIN CERTIFICATE-----\nBAUG\n-----END CERTIFICATE-----\n"
+///  );
+///  # Ok::<(), std::io::Error>(())
+/// ```
+pub struct Encoder<W: Write> {
+    output: IoWriter<BufWriter<W>>,
+    config: EncodeConfig,
+    state: State,
+}
+
+/// What was written last
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Start,
+    /// A block, without the line ending of its END line yet
+    Block,
+    Text,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Create a new encoder writing to `writer` with the default
+    /// configuration
+    pub fn new(writer: W) -> Self {
+        Encoder {
+            output: IoWriter::new(BufWriter::new(writer)),
+            config: EncodeConfig::default(),
+            state: State::Start,
+        }
+    }
+
+    /// Set the configuration to encode the blocks with
+    pub fn set_config(mut self, config: EncodeConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Write a block, preceded by the separator if the previous item was
+    /// a block too
+    pub fn write_block(&mut self, pem: &Pem) -> io::Result<()> {
+        let config = self.config.set_trailing_newline(false);
+        let result = self.end_block().and_then(|()| {
+            if self.state == State::Block {
+                self.output.write_str(self.config.separator())?;
+            }
+            write_pem(&mut self.output, pem, config)
+        });
+        self.state = State::Block;
+        result.map_err(|_| self.output.take_error())
+    }
+
+    /// Write explanatory text, ending it with a line break if it doesn't
+    /// end with one
+    pub fn write_text(&mut self, text: &str) -> io::Result<()> {
+        let result = self.end_block().and_then(|()| {
+            self.output.write_str(text)?;
+            if !text.is_empty() && !text.ends_with('\n') {
+                self.output.write_str(self.config.line_ending.as_str())?;
+            }
+            Ok(())
+        });
+        self.state = State::Text;
+        result.map_err(|_| self.output.take_error())
+    }
+
+    /// Flush the output written so far to the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.output.inner.flush()
+    }
+
+    /// Write the trailing newline, if configured, and flush the output,
+    /// returning the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.state == State::Block && self.config.trailing_newline {
+            self.end_block().map_err(|_| self.output.take_error())?;
+        }
+        self.output.inner.into_inner().map_err(|e| e.into_error())
+    }
+
+    /// Write the line ending of the END line of the previous block
+    fn end_block(&mut self) -> fmt::Result {
+        if self.state == State::Block {
+            self.output.write_str(self.config.line_ending.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> fmt::Debug for Encoder<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Encoder")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode_many, encode_many_config, parse_many, LineEnding};
+
+    #[test]
+    fn test_encoder_matches_encode_many() {
+        let pems = [Pem::new("FOO", [1, 2, 3]), Pem::new("BAR", [4, 5, 6])];
+        let configs = [
+            EncodeConfig::default(),
+            EncodeConfig::new().set_line_ending(LineEnding::LF),
+            EncodeConfig::new()
+                .set_block_separator("")
+                .set_trailing_newline(false),
+        ];
+        for config in configs {
+            let mut encoder = Encoder::new(Vec::new()).set_config(config);
+            for pem in &pems {
+                encoder.write_block(pem).unwrap();
+            }
+            let output = encoder.finish().unwrap();
+            assert_eq!(output, encode_many_config(&pems, config).as_bytes());
+        }
+
+        let output = Encoder::new(Vec::new()).finish().unwrap();
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_encoder_text() {
+        let pems = [Pem::new("FOO", [1, 2, 3]), Pem::new("BAR", [4, 5, 6])];
+        let mut encoder = Encoder::new(Vec::new());
+        encoder.write_text("# leaf").unwrap();
+        encoder.write_block(&pems[0]).unwrap();
+        encoder.write_text("# chain\r\n").unwrap();
+        encoder.write_block(&pems[1]).unwrap();
+        encoder.flush().unwrap();
+        let output = String::from_utf8(encoder.finish().unwrap()).unwrap();
+        assert!(output.starts_with("# leaf\r\n-----BEGIN FOO-----"));
+        assert!(output.contains("-----END FOO-----\r\n# chain\r\n-----BEGIN BAR-----"));
+        assert!(output.ends_with("-----END BAR-----\r\n"));
+        assert_eq!(parse_many(&output).unwrap(), pems);
+        assert_ne!(output, encode_many(&pems));
+    }
+}
@@ -3,17 +3,39 @@
 // Licensed under the MIT license <LICENSE.md or
 // http://opensource.org/licenses/MIT>. This file may not be
 // copied, modified, or distributed except according to those terms.
+use crate::Location;
 use core::fmt;
 
 #[cfg(any(feature = "std", test))]
 use std::error::Error;
 
 #[cfg(not(any(feature = "std", test)))]
-use alloc::string::String;
+use alloc::{boxed::Box, string::String};
 
 /// The `pem` error type.
+///
+/// New variants may be added in minor releases; match on
+/// [`PemError::kind`] to handle categories of errors without listing every
+/// variant.
+///
+/// With [`ParseConfig::set_error_locations`](crate::ParseConfig::set_error_locations),
+/// errors are wrapped in [`PemError::Located`]. To match the cause the
+/// same way whether or not locations are enabled, match on
+/// [`PemError::inner`], and get the location from [`PemError::location`].
+///
+/// # Example
+/// ```rust
+///  use pem::{parse_config, ParseConfig, PemError};
+///
+///  let input = "-----BEGIN FOO-----\nA?ID\n-----END FOO-----\n";
+///  for config in [ParseConfig::new(), ParseConfig::new().set_error_locations(true)] {
+///      let err = parse_config(input, &config).unwrap_err();
+///      assert!(matches!(err.inner(), PemError::InvalidData(_)));
+///  }
+/// ```
 #[derive(Debug, Eq, PartialEq)]
 #[allow(missing_docs)]
+#[non_exhaustive]
 pub enum PemError {
     MismatchedTags(String, String),
     MalformedFraming,
@@ -23,6 +45,111 @@ pub enum PemError {
     InvalidData(::base64::DecodeError),
     InvalidHeader(String),
     NotUtf8(::core::str::Utf8Error),
+    Rejected(String, String),
+    UnknownTag(String),
+    ConflictingDuplicate(String),
+    BudgetExceeded(usize),
+    MalformedDer,
+    BufferTooSmall,
+    MissingDekInfo,
+    InvalidDekInfo(String),
+    ChecksumMismatch,
+    InvalidLabel(String),
+    UnexpectedTag(String, String),
+    TooLarge(usize),
+    TooManyBlocks(usize),
+    LineTooLong(usize),
+    UnchainedCertificate(usize),
+    UnsupportedAlgorithm(String),
+    InvalidComment(String),
+    NotDer(String),
+    Located(Location, Box<PemError>),
+}
+
+/// The category of a [`PemError`]
+///
+/// # Example
+/// ```rust
+///  use pem::ErrorKind;
+///
+///  let err = pem::parse("-----BEGIN FOO-----\nAQID\n-----END BAR-----\n").unwrap_err();
+///  assert_eq!(err.kind(), ErrorKind::Framing);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The BEGIN and END boundaries are missing, mismatched or malformed
+    Framing,
+    /// The base64 data is missing or invalid, or the input is not UTF-8
+    Data,
+    /// An RFC 1421 header is invalid or missing
+    Header,
+    /// A label is invalid or not the one expected
+    Label,
+    /// A block was refused by a [`ParseConfig`](crate::ParseConfig) policy
+    Policy,
+    /// A configured or built-in limit was exceeded
+    Limit,
+    /// The binary contents of a block cannot be interpreted
+    Contents,
+}
+
+impl PemError {
+    /// Get the category of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            PemError::MismatchedTags(..)
+            | PemError::MalformedFraming
+            | PemError::MissingBeginTag
+            | PemError::MissingEndTag
+            | PemError::InvalidComment(_) => ErrorKind::Framing,
+            PemError::MissingData
+            | PemError::InvalidData(_)
+            | PemError::NotUtf8(_)
+            | PemError::ChecksumMismatch => ErrorKind::Data,
+            PemError::InvalidHeader(_) | PemError::MissingDekInfo | PemError::InvalidDekInfo(_) => {
+                ErrorKind::Header
+            }
+            PemError::InvalidLabel(_) | PemError::UnknownTag(_) | PemError::UnexpectedTag(..) => {
+                ErrorKind::Label
+            }
+            PemError::Rejected(..) | PemError::ConflictingDuplicate(_) => ErrorKind::Policy,
+            PemError::BudgetExceeded(_)
+            | PemError::BufferTooSmall
+            | PemError::TooLarge(_)
+            | PemError::TooManyBlocks(_)
+            | PemError::LineTooLong(_) => ErrorKind::Limit,
+            PemError::MalformedDer
+            | PemError::UnchainedCertificate(_)
+            | PemError::UnsupportedAlgorithm(_)
+            | PemError::NotDer(_) => ErrorKind::Contents,
+            PemError::Located(_, e) => e.kind(),
+        }
+    }
+
+    /// Get the error without its location, the cause to match on
+    pub fn inner(&self) -> &PemError {
+        match self {
+            PemError::Located(_, e) => e.inner(),
+            e => e,
+        }
+    }
+
+    /// Convert into the error without its location
+    pub fn into_inner(self) -> PemError {
+        match self {
+            PemError::Located(_, e) => e.into_inner(),
+            e => e,
+        }
+    }
+
+    /// Get the location of the error in the input, if known
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            PemError::Located(location, _) => Some(*location),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for PemError {
@@ -38,6 +165,33 @@ impl fmt::Display for PemError {
             PemError::InvalidData(e) => write!(f, "invalid data: {e}"),
             PemError::InvalidHeader(hdr) => write!(f, "invalid header: {hdr}"),
             PemError::NotUtf8(e) => write!(f, "invalid utf-8 value: {e}"),
+            PemError::Rejected(tag, reason) => write!(f, "rejected {tag} block: {reason}"),
+            PemError::UnknownTag(tag) => write!(f, "unknown tag: {tag}"),
+            PemError::ConflictingDuplicate(tag) => {
+                write!(f, "conflicting duplicates of {tag} block")
+            }
+            PemError::BudgetExceeded(budget) => {
+                write!(f, "allocation budget of {budget} bytes exceeded")
+            }
+            PemError::MalformedDer => write!(f, "malformed DER contents"),
+            PemError::BufferTooSmall => write!(f, "output buffer too small"),
+            PemError::MissingDekInfo => write!(f, "encrypted block without DEK-Info header"),
+            PemError::InvalidDekInfo(value) => write!(f, "invalid DEK-Info header: {value}"),
+            PemError::ChecksumMismatch => write!(f, "armor checksum mismatch"),
+            PemError::InvalidLabel(label) => write!(f, "invalid label: {label:?}"),
+            PemError::UnexpectedTag(expected, found) => {
+                write!(f, "expected \"{expected}\" block, found \"{found}\"")
+            }
+            PemError::TooLarge(max) => write!(f, "block contents exceed {max} bytes"),
+            PemError::TooManyBlocks(max) => write!(f, "more than {max} blocks"),
+            PemError::LineTooLong(max) => write!(f, "line exceeds {max} bytes"),
+            PemError::UnchainedCertificate(index) => {
+                write!(f, "certificate {index} is not part of the chain")
+            }
+            PemError::UnsupportedAlgorithm(oid) => write!(f, "unsupported algorithm: {oid}"),
+            PemError::InvalidComment(line) => write!(f, "invalid comment line: {line:?}"),
+            PemError::NotDer(reason) => write!(f, "contents are not DER: {reason}"),
+            PemError::Located(location, e) => write!(f, "{e} ({location})"),
         }
     }
 }
@@ -49,12 +203,26 @@ impl Error for PemError {
             // Errors originating from other libraries.
             PemError::InvalidData(e) => Some(e),
             PemError::NotUtf8(e) => Some(e),
+            PemError::Located(_, e) => e.source(),
             // Errors directly originating from `pem-rs`.
             _ => None,
         }
     }
 }
 
+// `core::error::Error` needs Rust 1.81, so it is opt-in for no_std builds
+#[cfg(all(feature = "core-error", not(any(feature = "std", test))))]
+impl core::error::Error for PemError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            // `base64` only implements `Error` for its errors with `std`.
+            PemError::NotUtf8(e) => Some(e),
+            PemError::Located(_, e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
 /// The `pem` result type.
 pub type Result<T> = ::core::result::Result<T, PemError>;
 
@@ -0,0 +1,311 @@
+use crate::label::is_valid_label;
+use core::{fmt, ops::Range};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{vec, vec::Vec};
+
+/// The role of a line in PEM-encoded data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Text outside of any block, before a block
+    PreText,
+    /// A `-----BEGIN ...-----` boundary
+    Begin,
+    /// An RFC 1421 header, or the continuation of one
+    Header,
+    /// An empty line inside a block, e.g. separating headers from the body
+    Blank,
+    /// A line of base64 body data
+    Body,
+    /// An OpenPGP-style `=XXXX` checksum line ending the body
+    Checksum,
+    /// A `-----END ...-----` boundary
+    End,
+    /// Text outside of any block, after the last block
+    TrailingText,
+}
+
+/// A problem found on a line by [`explain`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    /// The label does not follow the RFC 7468 grammar
+    InvalidLabel,
+    /// The label of the END boundary differs from the BEGIN boundary
+    MismatchedLabels,
+    /// No END boundary follows this BEGIN boundary
+    MissingEnd,
+    /// There is no BEGIN boundary for this END boundary
+    UnmatchedEnd,
+    /// The headers are not followed by an empty line
+    MissingSeparator,
+    /// The body line contains characters other than base64
+    InvalidBase64,
+}
+
+/// A line of input classified by [`explain`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplainedLine {
+    /// The 1-based line number
+    pub number: usize,
+    /// The byte range of the line in the input, without the line ending
+    pub span: Range<usize>,
+    /// The role of the line
+    pub kind: LineKind,
+    /// The problems found on the line
+    pub problems: Vec<Problem>,
+}
+
+/// Classify every line of PEM-encoded data, attaching any problems to the
+/// offending line.
+///
+/// Lines may end with LF, CRLF or CR. The classification is lenient like
+/// the parser: problems are reported, but never stop the explanation.
+///
+/// # Example
+/// ```rust
+///  use pem::{explain, LineKind, Problem};
+///
+///  let input = "key for prod\n-----BEGIN FOO-----\nAQ?D\n-----END BAR-----\n";
+///  let lines = explain(input);
+///  let kinds: Vec<_> = lines.iter().map(|line| line.kind).collect();
+///  assert_eq!(kinds, [LineKind::PreText, LineKind::Begin, LineKind::Body, LineKind::End]);
+///  assert_eq!(lines[2].problems, [Problem::InvalidBase64]);
+///  assert_eq!(lines[3].problems, [Problem::MismatchedLabels]);
+/// ```
+pub fn explain<B: AsRef<[u8]>>(input: B) -> Vec<ExplainedLine> {
+    let input = input.as_ref();
+    let lines = split_lines(input);
+    let mut explained: Vec<ExplainedLine> = Vec::with_capacity(lines.len());
+    // Index of the open BEGIN line and its label
+    let mut open: Option<(usize, &[u8])> = None;
+    let mut in_headers = false;
+    let mut has_headers = false;
+    let mut last_end = None;
+
+    for (index, span) in lines.iter().enumerate() {
+        let text = &input[span.clone()];
+        let mut problems = vec![];
+        let kind = if let Some(label) = boundary_label(text, b"-----BEGIN ") {
+            if let Some((begin, _)) = open {
+                explained[begin].problems.push(Problem::MissingEnd);
+            }
+            if !is_valid_label(label) {
+                problems.push(Problem::InvalidLabel);
+            }
+            open = Some((index, label));
+            in_headers = true;
+            has_headers = false;
+            LineKind::Begin
+        } else if let Some(label) = boundary_label(text, b"-----END ") {
+            match open.take() {
+                Some((_, begin)) if begin != label => problems.push(Problem::MismatchedLabels),
+                Some(_) => {}
+                None => problems.push(Problem::UnmatchedEnd),
+            }
+            if !is_valid_label(label) {
+                problems.push(Problem::InvalidLabel);
+            }
+            last_end = Some(index);
+            LineKind::End
+        } else if open.is_none() {
+            LineKind::PreText
+        } else {
+            let trimmed = trim_end(text);
+            let continuation = matches!(text.first(), Some(b' ' | b'\t'));
+            if trimmed.is_empty() {
+                in_headers = false;
+                LineKind::Blank
+            } else if in_headers && (text.contains(&b':') || (has_headers && continuation)) {
+                has_headers = true;
+                LineKind::Header
+            } else {
+                if in_headers && has_headers {
+                    problems.push(Problem::MissingSeparator);
+                }
+                in_headers = false;
+                let next_is_end = lines
+                    .get(index + 1)
+                    .map_or(false, |next| input[next.clone()].starts_with(b"-----END "));
+                if next_is_end && is_checksum(trimmed) {
+                    LineKind::Checksum
+                } else {
+                    if !trimmed.iter().all(|&c| is_base64_char(c) || c == b'=') {
+                        problems.push(Problem::InvalidBase64);
+                    }
+                    LineKind::Body
+                }
+            }
+        };
+        explained.push(ExplainedLine {
+            number: index + 1,
+            span: span.clone(),
+            kind,
+            problems,
+        });
+    }
+
+    if let Some((begin, _)) = open {
+        explained[begin].problems.push(Problem::MissingEnd);
+    }
+    if let Some(last_end) = last_end {
+        for line in &mut explained[last_end + 1..] {
+            if line.kind == LineKind::PreText {
+                line.kind = LineKind::TrailingText;
+            }
+        }
+    }
+    explained
+}
+
+/// Split the input into line spans, without the LF, CRLF or CR endings
+fn split_lines(input: &[u8]) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while start < input.len() {
+        match input[start..]
+            .iter()
+            .position(|&c| c == b'\r' || c == b'\n')
+        {
+            Some(len) => {
+                let end = start + len;
+                lines.push(start..end);
+                start = if input[end..].starts_with(b"\r\n") {
+                    end + 2
+                } else {
+                    end + 1
+                };
+            }
+            None => {
+                lines.push(start..input.len());
+                break;
+            }
+        }
+    }
+    lines
+}
+
+fn boundary_label<'a>(line: &'a [u8], prefix: &[u8]) -> Option<&'a [u8]> {
+    trim_end(line).strip_prefix(prefix)?.strip_suffix(b"-----")
+}
+
+fn trim_end(mut line: &[u8]) -> &[u8] {
+    while let [rest @ .., b' ' | b'\t'] = line {
+        line = rest;
+    }
+    line
+}
+
+fn is_base64_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'+' || c == b'/'
+}
+
+/// `=` followed by the four base64 characters of a CRC-24
+fn is_checksum(line: &[u8]) -> bool {
+    matches!(line, [b'=', crc @ ..] if crc.len() == 4 && crc.iter().all(|&c| is_base64_char(c)))
+}
+
+impl fmt::Display for LineKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self {
+            LineKind::PreText => "text before block",
+            LineKind::Begin => "BEGIN boundary",
+            LineKind::Header => "header",
+            LineKind::Blank => "blank line",
+            LineKind::Body => "body",
+            LineKind::Checksum => "checksum",
+            LineKind::End => "END boundary",
+            LineKind::TrailingText => "text after block",
+        };
+        f.write_str(kind)
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let problem = match self {
+            Problem::InvalidLabel => "invalid label",
+            Problem::MismatchedLabels => "END label does not match BEGIN label",
+            Problem::MissingEnd => "missing END boundary",
+            Problem::UnmatchedEnd => "END boundary without BEGIN boundary",
+            Problem::MissingSeparator => "missing blank line after headers",
+            Problem::InvalidBase64 => "invalid base64 characters",
+        };
+        f.write_str(problem)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn kinds(input: &str) -> Vec<LineKind> {
+        explain(input).into_iter().map(|line| line.kind).collect()
+    }
+
+    #[test]
+    fn test_explain_classification() {
+        use LineKind::*;
+
+        let input = "-----BEGIN PGP MESSAGE-----\r\n\
+                     Version: 1\r\n\
+                     Comment: long\r\n \tcomment\r\n\
+                     \r\n\
+                     AQID\r\n\
+                     =njUN\r\n\
+                     -----END PGP MESSAGE-----\r\n\
+                     between\r\n\
+                     -----BEGIN FOO-----\r\n\
+                     AQID\r\n\
+                     -----END FOO-----\r\n\
+                     after";
+        assert_eq!(
+            kinds(input),
+            [
+                Begin,
+                Header,
+                Header,
+                Header,
+                Blank,
+                Body,
+                Checksum,
+                End,
+                PreText,
+                Begin,
+                Body,
+                End,
+                TrailingText
+            ]
+        );
+        let lines = explain(input);
+        assert!(lines.iter().all(|line| line.problems.is_empty()));
+        assert_eq!(lines[12].number, 13);
+        assert_eq!(&input[lines[12].span.clone()], "after");
+    }
+
+    #[test]
+    fn test_explain_problems() {
+        let input = "-----END FOO-----\n\
+                     -----BEGIN  FOO-----\n\
+                     -----BEGIN BAR-----\n\
+                     Proc-Type: 4,ENCRYPTED\n\
+                     AQID\n\
+                     -----END BAZ-----\n\
+                     -----BEGIN QUX-----\n";
+        let problems: Vec<_> = explain(input)
+            .into_iter()
+            .map(|line| line.problems)
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                vec![Problem::UnmatchedEnd],
+                vec![Problem::InvalidLabel, Problem::MissingEnd],
+                vec![],
+                vec![],
+                vec![Problem::MissingSeparator],
+                vec![Problem::MismatchedLabels],
+                vec![Problem::MissingEnd],
+            ]
+        );
+    }
+}
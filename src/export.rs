@@ -0,0 +1,119 @@
+use crate::{parse_captures_iter, Pem};
+use base64::Engine as _;
+use std::io::{self, Write};
+
+/// Write every block of `input` to `writer` as one JSON object per line,
+/// e.g. for piping bundle contents into `jq`.
+///
+/// Each object has the fields `tag`, `headers` (an array of `[key, value]`
+/// pairs), `contents` (standard base64), and `start` and `end`, the byte
+/// range of the block in `input`. Parsing stops at the first invalid block
+/// with an error of kind `InvalidData`, after the preceding blocks have
+/// been written.
+///
+/// # Example
+/// ```rust
+///  let input = "-----BEGIN FOO-----\nAQID\n-----END FOO-----\n";
+///  let mut out = Vec::new();
+///  pem::write_json_lines(input, &mut out).unwrap();
+///  assert_eq!(
+///      String::from_utf8(out).unwrap(),
+///      "{\"tag\":\"FOO\",\"headers\":[],\"contents\":\"AQID\",\"start\":0,\"end\":42}\n"
+///  );
+/// ```
+pub fn write_json_lines<B, W>(input: B, mut writer: W) -> io::Result<()>
+where
+    B: AsRef<[u8]>,
+    W: Write,
+{
+    let mut matches = parse_captures_iter(input.as_ref());
+    while let Some((span, caps)) = matches.next_spanned() {
+        let pem = Pem::new_from_captures(caps)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut line = String::from("{\"tag\":");
+        push_json_string(&mut line, pem.tag());
+        line.push_str(",\"headers\":[");
+        for (i, (key, value)) in pem.headers().iter().enumerate() {
+            if i > 0 {
+                line.push(',');
+            }
+            line.push('[');
+            push_json_string(&mut line, key);
+            line.push(',');
+            push_json_string(&mut line, value);
+            line.push(']');
+        }
+        line.push_str("],\"contents\":\"");
+        base64::engine::general_purpose::STANDARD.encode_string(pem.contents(), &mut line);
+        line.push_str(&format!(
+            "\",\"start\":{},\"end\":{}}}\n",
+            span.start, span.end
+        ));
+        writer.write_all(line.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => out.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_json_lines() {
+        let input = "-----BEGIN FOO-----\n\
+                     Proc-Type: 4,ENCRYPTED\n\
+                     Comment: \"quoted\" \\ \x01\n\
+                     \n\
+                     AQID\n\
+                     -----END FOO-----\n\
+                     -----BEGIN BAR-----\nBAUG\n-----END BAR-----\n";
+        let mut out = Vec::new();
+        write_json_lines(input, &mut out).unwrap();
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                json!({
+                    "tag": "FOO",
+                    "headers": [["Proc-Type", "4,ENCRYPTED"], ["Comment", "\"quoted\" \\ \u{1}"]],
+                    "contents": "AQID",
+                    "start": 0,
+                    "end": 88,
+                }),
+                json!({"tag": "BAR", "headers": [], "contents": "BAUG", "start": 89, "end": 131}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_lines_stops_at_errors() {
+        let input = "-----BEGIN FOO-----\nAQID\n-----END FOO-----\n\
+                     -----BEGIN FOO-----\nAQID\n-----END BAR-----\n";
+        let mut out = Vec::new();
+        let err = write_json_lines(input, &mut out).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 1);
+    }
+}
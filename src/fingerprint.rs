@@ -0,0 +1,166 @@
+//! Fingerprints of the binary contents of Pem blocks.
+//!
+//! The digest algorithm is pluggable through the [`Digest`] trait, so the
+//! backend (RustCrypto, ring, aws-lc, ...) and the algorithm are up to the
+//! caller. The `sha` feature adds built-in SHA-256 and SHA-1 digests.
+//!
+//! ```rust
+//!  use pem::{fingerprint::Digest, Pem};
+//!
+//!  /// Stand-in for a real algorithm, e.g. wrapping `sha2::Sha256::digest`
+//!  struct Sum;
+//!
+//!  impl Digest for Sum {
+//!      type Output = [u8; 2];
+//!
+//!      fn digest(data: &[u8]) -> [u8; 2] {
+//!          let sum = data.iter().map(|&b| u16::from(b)).sum::<u16>();
+//!          sum.to_be_bytes()
+//!      }
+//!  }
+//!
+//!  let pem = Pem::new("CERTIFICATE", [0xff, 0xff, 0x0f]);
+//!  assert_eq!(pem.fingerprint::<Sum>().to_string(), "02:0D");
+//! ```
+use crate::Pem;
+use core::fmt;
+
+/// A digest algorithm used to compute fingerprints
+pub trait Digest {
+    /// The digest value
+    type Output: AsRef<[u8]>;
+
+    /// Compute the digest of `data`
+    fn digest(data: &[u8]) -> Self::Output;
+}
+
+/// The fingerprint of a Pem block
+///
+/// Displays like OpenSSL, as colon-separated uppercase hex bytes
+/// (`AB:CD:...`). The `LowerHex` and `UpperHex` formats print the plain hex
+/// digits instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fingerprint<T>(T);
+
+impl<T: AsRef<[u8]>> Fingerprint<T> {
+    /// Get the raw digest bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+
+    /// Consume the fingerprint to get the digest value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: AsRef<[u8]>> fmt::Display for Fingerprint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, byte) in self.as_bytes().iter().enumerate() {
+            if i > 0 {
+                f.write_str(":")?;
+            }
+            write!(f, "{byte:02X}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]>> fmt::LowerHex for Fingerprint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_bytes()
+            .iter()
+            .try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl<T: AsRef<[u8]>> fmt::UpperHex for Fingerprint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_bytes()
+            .iter()
+            .try_for_each(|byte| write!(f, "{byte:02X}"))
+    }
+}
+
+/// SHA-256, with the `sha` feature
+#[cfg(feature = "sha")]
+#[derive(Debug, Clone, Copy)]
+pub struct Sha256;
+
+#[cfg(feature = "sha")]
+impl Digest for Sha256 {
+    type Output = [u8; 32];
+
+    fn digest(data: &[u8]) -> [u8; 32] {
+        crate::sha::sha256(data)
+    }
+}
+
+/// SHA-1, with the `sha` feature, for matching legacy fingerprints
+#[cfg(feature = "sha")]
+#[derive(Debug, Clone, Copy)]
+pub struct Sha1;
+
+#[cfg(feature = "sha")]
+impl Digest for Sha1 {
+    type Output = [u8; 20];
+
+    fn digest(data: &[u8]) -> [u8; 20] {
+        crate::sha::sha1(data)
+    }
+}
+
+impl Pem {
+    /// Compute the fingerprint of the binary contents with the digest `D`
+    pub fn fingerprint<D: Digest>(&self) -> Fingerprint<D::Output> {
+        Fingerprint(D::digest(&self.contents))
+    }
+
+    /// Compute the SHA-256 fingerprint of the binary contents
+    ///
+    /// # Example
+    /// ```rust
+    ///  let pem = pem::Pem::new("CERTIFICATE", *b"abc");
+    ///  assert!(pem.sha256_fingerprint().to_string().starts_with("BA:78:16:BF:"));
+    /// ```
+    #[cfg(feature = "sha")]
+    pub fn sha256_fingerprint(&self) -> Fingerprint<[u8; 32]> {
+        self.fingerprint::<Sha256>()
+    }
+
+    /// Compute the SHA-1 fingerprint of the binary contents
+    #[cfg(feature = "sha")]
+    pub fn sha1_fingerprint(&self) -> Fingerprint<[u8; 20]> {
+        self.fingerprint::<Sha1>()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Identity;
+
+    impl Digest for Identity {
+        type Output = Vec<u8>;
+
+        fn digest(data: &[u8]) -> Vec<u8> {
+            data.to_vec()
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_formats() {
+        let fingerprint = Pem::new("TEST", [0x0a, 0xbc, 0xde]).fingerprint::<Identity>();
+        assert_eq!(fingerprint.to_string(), "0A:BC:DE");
+        assert_eq!(format!("{fingerprint:x}"), "0abcde");
+        assert_eq!(format!("{fingerprint:X}"), "0ABCDE");
+        assert_eq!(fingerprint.into_inner(), [0x0a, 0xbc, 0xde]);
+    }
+
+    #[test]
+    fn test_empty_fingerprint() {
+        let fingerprint = Pem::new("TEST", []).fingerprint::<Identity>();
+        assert_eq!(fingerprint.to_string(), "");
+    }
+}
@@ -0,0 +1,368 @@
+use crate::{encode, parse, parse_many, Pem, PemBundle};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Parses all PEM-encoded blocks of a file
+///
+/// Invalid data is reported as an error of kind `InvalidData`.
+///
+/// # Example
+/// ```rust,no_run
+///  let certs = pem::parse_many_from_file("chain.pem")?;
+///  # Ok::<(), std::io::Error>(())
+/// ```
+pub fn parse_many_from_file(path: impl AsRef<Path>) -> io::Result<Vec<Pem>> {
+    parse_many(fs::read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// File extensions loaded by [`load_dir`]
+const BUNDLE_EXTENSIONS: &[&str] = &["pem", "crt"];
+
+/// Parses every `.pem` and `.crt` file in a directory and its
+/// subdirectories, such as `/etc/ssl/certs`
+///
+/// Returns all blocks along with the path of the file they were read from,
+/// ordered by path and then by position in the file. Symbolic links to
+/// files are followed, symbolic links to directories are not. A file with
+/// invalid data fails the whole load with an error of kind `InvalidData`
+/// naming the file.
+///
+/// # Example
+/// ```rust,no_run
+///  for (path, pem) in pem::load_dir("/etc/ssl/certs")? {
+///      println!("{}: {}", path.display(), pem.tag());
+///  }
+///  # Ok::<(), std::io::Error>(())
+/// ```
+pub fn load_dir(path: impl AsRef<Path>) -> io::Result<Vec<(PathBuf, Pem)>> {
+    let mut files = Vec::new();
+    collect_files(path.as_ref(), &mut files)?;
+    files.sort();
+
+    let mut pems = Vec::new();
+    for file in files {
+        let parsed = parse_many(fs::read(&file)?).map_err(|e| {
+            let message = format!("{}: {}", file.display(), e);
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        })?;
+        pems.extend(parsed.into_iter().map(|pem| (file.clone(), pem)));
+    }
+    Ok(pems)
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, files)?;
+            continue;
+        }
+        let is_bundle = path.extension().map_or(false, |ext| {
+            BUNDLE_EXTENSIONS
+                .iter()
+                .any(|bundle| ext.eq_ignore_ascii_case(bundle))
+        });
+        if is_bundle && fs::metadata(&path)?.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Parses a bundle and writes each block to its own file in `dir`,
+/// returning the paths written
+///
+/// `naming` gives the path of each block's file relative to `dir`, from
+/// its index and the block; [`block_file_name`] names them like
+/// `0-certificate.pem`. Files are replaced atomically and private keys get
+/// restricted permissions, as with [`Pem::write_to_file`]. Invalid data is
+/// reported as an error of kind `InvalidData` before anything is written.
+///
+/// # Example
+/// ```rust,no_run
+///  let input = std::fs::read("fullchain.pem")?;
+///  let paths = pem::split_to_dir(input, "certs", pem::block_file_name)?;
+///  # Ok::<(), std::io::Error>(())
+/// ```
+pub fn split_to_dir(
+    input: impl AsRef<[u8]>,
+    dir: impl AsRef<Path>,
+    naming: impl Fn(usize, &Pem) -> PathBuf,
+) -> io::Result<Vec<PathBuf>> {
+    let pems = parse_many(input).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut paths = Vec::with_capacity(pems.len());
+    for (index, pem) in pems.iter().enumerate() {
+        let path = dir.as_ref().join(naming(index, pem));
+        pem.write_to_file(&path)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Name the file of a block by its index and its tag in lower case, with
+/// runs of other characters than letters and digits replaced by dashes,
+/// such as `0-certificate.pem` or `1-rsa-private-key.pem`
+pub fn block_file_name(index: usize, pem: &Pem) -> PathBuf {
+    let mut name = format!("{index}-");
+    for word in pem
+        .tag()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        name.push_str(&word.to_ascii_lowercase());
+        name.push('-');
+    }
+    name.pop();
+    name.push_str(".pem");
+    PathBuf::from(name)
+}
+
+impl PemBundle {
+    /// Parse all blocks of a file and add them to the end of the bundle
+    pub fn extend_from_file(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.extend(parse_many_from_file(path)?);
+        Ok(())
+    }
+}
+
+impl Pem {
+    /// Parse a single PEM-encoded block from a file
+    ///
+    /// Invalid data is reported as an error of kind `InvalidData`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///  let key = pem::Pem::read_from_file("key.pem")?;
+    ///  # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn read_from_file(path: impl AsRef<Path>) -> io::Result<Pem> {
+        parse(fs::read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Write the PEM encoding to a file, replacing it atomically
+    ///
+    /// The data is written to a temporary file in the same directory, which
+    /// is then renamed over `path`, so readers never see a partially written
+    /// file. On Unix, files holding private key material are created with
+    /// mode 0600.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///  let key = pem::Pem::new("PRIVATE KEY", vec![0x30, 0x00]);
+    ///  key.write_to_file("key.pem")?;
+    ///  # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_atomic(
+            path.as_ref(),
+            encode(self).as_bytes(),
+            self.is_private_key(),
+        )
+    }
+
+    /// Write the binary contents to a file, such as a `.der` file
+    ///
+    /// Like [`Pem::write_to_file`], the file is replaced atomically and, on
+    /// Unix, created with mode 0600 if it holds private key material.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///  let pem = pem::parse(std::fs::read("cert.pem")?).unwrap();
+    ///  pem.write_der_to("cert.der")?;
+    ///  # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn write_der_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_atomic(path.as_ref(), &self.contents, self.is_private_key())
+    }
+
+    /// Create a new Pem struct from the binary contents of a file, such as
+    /// a `.der` file
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///  let pem = pem::Pem::from_der_file("CERTIFICATE", "cert.der")?;
+    ///  std::fs::write("cert.pem", pem.to_string())?;
+    ///  # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_der_file(tag: impl ToString, path: impl AsRef<Path>) -> io::Result<Pem> {
+        Ok(Pem::new(tag, fs::read(path)?))
+    }
+}
+
+/// Write `data` to a temporary file next to `path`, then rename it over
+/// `path`
+fn write_atomic(path: &Path, data: &[u8], private: bool) -> io::Result<()> {
+    let tmp = temp_path(path)?;
+    let result = write_new_file(&tmp, data, private).and_then(|()| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn write_new_file(path: &Path, data: &[u8], private: bool) -> io::Result<()> {
+    use io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(data)?;
+    file.sync_all()
+}
+
+/// A path for a temporary file next to `path`, unique within the process
+fn temp_path(path: &Path) -> io::Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(name);
+    tmp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    Ok(path.with_file_name(tmp_name))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_der_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("pem-der-{}.der", std::process::id()));
+        let pem = Pem::new("TEST", vec![0x30, 0x03, 1, 2, 3]);
+        pem.write_der_to(&path).unwrap();
+        let read = Pem::from_der_file("TEST", &path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), pem);
+
+        let key = Pem::new("PRIVATE KEY", vec![0x30, 0x03, 1, 2, 3]);
+        key.write_der_to(&path).unwrap();
+        // Replacing an existing file works too
+        key.write_der_to(&path).unwrap();
+        let read = Pem::from_der_file("PRIVATE KEY", &path);
+        #[cfg(unix)]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            fs::metadata(&path).unwrap().permissions().mode()
+        };
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), key);
+        #[cfg(unix)]
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_pem_file_round_trip() {
+        let dir = std::env::temp_dir();
+        let key_path = dir.join(format!("pem-key-{}.pem", std::process::id()));
+        let key = Pem::new("PRIVATE KEY", vec![0x30, 0x03, 1, 2, 3]);
+        key.write_to_file(&key_path).unwrap();
+        // Replacing an existing file works too
+        key.write_to_file(&key_path).unwrap();
+        let read = Pem::read_from_file(&key_path);
+        let many = parse_many_from_file(&key_path);
+        #[cfg(unix)]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            fs::metadata(&key_path).unwrap().permissions().mode()
+        };
+        fs::remove_file(&key_path).unwrap();
+        assert_eq!(read.unwrap(), key);
+        assert_eq!(many.unwrap(), [key]);
+        #[cfg(unix)]
+        assert_eq!(mode & 0o777, 0o600);
+
+        let err = Pem::read_from_file(dir.join("pem-missing.pem")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = std::env::temp_dir().join(format!("pem-dir-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        let root = Pem::new("CERTIFICATE", [1]);
+        let chain = [Pem::new("CERTIFICATE", [2]), Pem::new("CERTIFICATE", [3])];
+        root.write_to_file(dir.join("nested").join("root.CRT"))
+            .unwrap();
+        fs::write(dir.join("chain.pem"), crate::encode_many(&chain)).unwrap();
+        fs::write(dir.join("notes.txt"), "not a bundle").unwrap();
+
+        let loaded = load_dir(&dir);
+        fs::write(
+            dir.join("broken.pem"),
+            "-----BEGIN A-----\n-----END B-----\n",
+        )
+        .unwrap();
+        let broken = load_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            loaded.unwrap(),
+            [
+                (dir.join("chain.pem"), chain[0].clone()),
+                (dir.join("chain.pem"), chain[1].clone()),
+                (dir.join("nested").join("root.CRT"), root),
+            ]
+        );
+        let err = broken.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("broken.pem"));
+    }
+
+    #[test]
+    fn test_split_to_dir() {
+        let dir = std::env::temp_dir().join(format!("pem-split-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let pems = [
+            Pem::new("CERTIFICATE", [1]),
+            Pem::new("RSA PRIVATE KEY", [2]),
+            Pem::new("X.509 CRL", [3]),
+        ];
+        let paths = split_to_dir(crate::encode_many(&pems), &dir, block_file_name).unwrap();
+        let written: Vec<_> = paths.iter().map(Pem::read_from_file).collect();
+        let custom = split_to_dir(encode(&pems[0]), &dir, |_, pem| {
+            PathBuf::from(format!("{}.crt", pem.contents()[0]))
+        });
+        let custom_read = Pem::read_from_file(dir.join("1.crt"));
+        let broken = split_to_dir(
+            "-----BEGIN A-----\n-----END B-----\n",
+            &dir,
+            block_file_name,
+        );
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            paths,
+            [
+                dir.join("0-certificate.pem"),
+                dir.join("1-rsa-private-key.pem"),
+                dir.join("2-x-509-crl.pem"),
+            ]
+        );
+        for (read, pem) in written.into_iter().zip(&pems) {
+            assert_eq!(read.unwrap(), *pem);
+        }
+        assert_eq!(custom.unwrap(), [dir.join("1.crt")]);
+        assert_eq!(custom_read.unwrap(), pems[0]);
+        assert_eq!(broken.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            block_file_name(7, &Pem::new("--", [])),
+            PathBuf::from("7.pem")
+        );
+    }
+}
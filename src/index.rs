@@ -0,0 +1,139 @@
+use crate::{parse_captures, parse_captures_iter, parse_ref, Pem, PemRef, Result};
+use core::ops::Range;
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::vec::Vec;
+
+/// The position of an indexed block
+#[derive(Debug, Clone)]
+struct Entry {
+    /// The label of the BEGIN boundary
+    tag: Range<usize>,
+    /// From the start of the BEGIN boundary to the end of the END boundary
+    bytes: Range<usize>,
+}
+
+/// Index over the blocks of a large input, such as a memory-mapped bundle
+///
+/// Building the index only locates the blocks, recording the byte range of
+/// each and of its tag, without decoding or copying anything. Individual
+/// blocks are then accessed or decoded on demand. Like
+/// [`parse_many`](crate::parse_many), text between blocks and an
+/// incomplete block at the end of the input are ignored.
+///
+/// # Example
+/// ```rust
+///  use pem::{encode_many, Pem, PemIndex};
+///
+///  let input = encode_many(&[Pem::new("X509 CRL", [1, 2, 3]), Pem::new("CERTIFICATE", [4, 5, 6])]);
+///  let index = PemIndex::new(input.as_bytes());
+///  assert_eq!(index.len(), 2);
+///  assert_eq!(index.find("CERTIFICATE"), Some(1));
+///  assert_eq!(index.get(1).unwrap()?.tag(), "CERTIFICATE");
+///  assert_eq!(index.decode(1).unwrap()?.contents(), [4, 5, 6]);
+///  # Ok::<(), pem::PemError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PemIndex<'a> {
+    input: &'a [u8],
+    entries: Vec<Entry>,
+}
+
+impl<'a> PemIndex<'a> {
+    /// Locate the blocks of `input`
+    pub fn new(input: &'a [u8]) -> Self {
+        let offset_of = |section: &[u8]| section.as_ptr() as usize - input.as_ptr() as usize;
+        let mut matches = parse_captures_iter(input);
+        let mut entries = Vec::new();
+        while let Some((bytes, caps)) = matches.next_spanned() {
+            let tag = offset_of(caps.begin)..offset_of(caps.begin) + caps.begin.len();
+            entries.push(Entry { tag, bytes });
+        }
+        PemIndex { input, entries }
+    }
+
+    /// Get the number of blocks
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the input holds no blocks
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the byte range of the `i`th block in the input, from the start
+    /// of the BEGIN boundary to the end of the END boundary
+    pub fn span(&self, i: usize) -> Option<Range<usize>> {
+        self.entries.get(i).map(|entry| entry.bytes.clone())
+    }
+
+    /// Find the first block with the given tag
+    pub fn find(&self, tag: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| &self.input[entry.tag.clone()] == tag.as_bytes())
+    }
+
+    /// Get the `i`th block without decoding it, checking its framing
+    pub fn get(&self, i: usize) -> Option<Result<PemRef<'a>>> {
+        let input = self.input;
+        self.entries
+            .get(i)
+            .map(|entry| parse_ref(&input[entry.bytes.clone()]))
+    }
+
+    /// Decode the `i`th block
+    pub fn decode(&self, i: usize) -> Option<Result<Pem>> {
+        let entry = self.entries.get(i)?;
+        let caps = parse_captures(&self.input[entry.bytes.clone()])?;
+        Some(Pem::new_from_captures(caps))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode, parse_many, PemError};
+
+    #[test]
+    fn test_index() {
+        let pems: Vec<Pem> = (0..50u8)
+            .map(|i| Pem::new(format!("BLOCK {}", i % 5), vec![i; i as usize]))
+            .collect();
+        let input: String = pems
+            .iter()
+            .map(|pem| format!("text\n{}", encode(pem)))
+            .collect();
+        let index = PemIndex::new(input.as_bytes());
+        assert_eq!(index.len(), 50);
+        let decoded: Vec<Pem> = (0..index.len())
+            .map(|i| index.decode(i).unwrap().unwrap())
+            .collect();
+        assert_eq!(decoded, parse_many(&input).unwrap());
+        assert_eq!(index.find("BLOCK 3"), Some(3));
+        assert_eq!(index.find("BLOCK"), None);
+        let span = index.span(7).unwrap();
+        assert!(input[span].starts_with("-----BEGIN BLOCK 2-----"));
+        assert!(index.get(50).is_none());
+        assert!(index.decode(50).is_none());
+    }
+
+    #[test]
+    fn test_index_invalid_blocks() {
+        let input = b"-----BEGIN FOO-----\nAQID\n-----END BAR-----\n\
+                      -----BEGIN BAZ-----\n!!!!\n-----END BAZ-----\n";
+        let index = PemIndex::new(input);
+        assert_eq!(index.len(), 2);
+        assert_eq!(
+            index.get(0).unwrap(),
+            Err(PemError::MismatchedTags("FOO".into(), "BAR".into()))
+        );
+        assert_eq!(index.get(1).unwrap().unwrap().tag(), "BAZ");
+        assert!(matches!(
+            index.decode(1).unwrap(),
+            Err(PemError::InvalidData(_))
+        ));
+        assert!(PemIndex::new(b"no blocks").is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{parse_many, Pem, Result, SectionKind, TagMatcher};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::vec::Vec;
+
+/// A block classified by [`read_items`], holding the DER contents of
+/// well-known certificate and key types
+///
+/// The variants match the DER types of TLS libraries such as rustls, e.g.
+/// `Item::Pkcs8Key(der)` becomes a `PrivatePkcs8KeyDer::from(der)`.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Item {
+    /// An X.509 certificate, from a `CERTIFICATE` block
+    X509Certificate(Vec<u8>),
+    /// A PKCS #1 RSA private key, from a `RSA PRIVATE KEY` block
+    Pkcs1Key(Vec<u8>),
+    /// A PKCS #8 private key, from a `PRIVATE KEY` block
+    Pkcs8Key(Vec<u8>),
+    /// A SEC 1 elliptic curve private key, from an `EC PRIVATE KEY` block
+    Sec1Key(Vec<u8>),
+    /// A certificate revocation list, from a `X509 CRL` block
+    Crl(Vec<u8>),
+    /// A PKCS #10 certification request, from a `CERTIFICATE REQUEST` block
+    Csr(Vec<u8>),
+    /// Any other block
+    Other(Pem),
+}
+
+impl From<Pem> for Item {
+    fn from(pem: Pem) -> Self {
+        let kind = pem.kind();
+        Item::classify(pem, kind)
+    }
+}
+
+impl Item {
+    /// Classify a block, recognizing its tag with `matcher`
+    ///
+    /// OpenSSL appends its trust settings to the certificate in
+    /// `TRUSTED CERTIFICATE` blocks; when such a block is classified as a
+    /// certificate, only the DER certificate is kept.
+    pub fn from_pem_with(matcher: &TagMatcher, pem: Pem) -> Self {
+        let kind = matcher.kind(pem.tag());
+        let is_trusted = pem.tag().eq_ignore_ascii_case("TRUSTED CERTIFICATE");
+        match Item::classify(pem, kind) {
+            Item::X509Certificate(mut der) if is_trusted => {
+                if let Some(len) = first_element_len(&der) {
+                    der.truncate(len);
+                }
+                Item::X509Certificate(der)
+            }
+            item => item,
+        }
+    }
+
+    fn classify(pem: Pem, kind: SectionKind) -> Self {
+        match kind {
+            SectionKind::Certificate => Item::X509Certificate(pem.into_contents()),
+            SectionKind::RsaPrivateKey => Item::Pkcs1Key(pem.into_contents()),
+            SectionKind::PrivateKey => Item::Pkcs8Key(pem.into_contents()),
+            SectionKind::EcPrivateKey => Item::Sec1Key(pem.into_contents()),
+            SectionKind::X509Crl => Item::Crl(pem.into_contents()),
+            SectionKind::CertificateRequest => Item::Csr(pem.into_contents()),
+            _ => Item::Other(pem),
+        }
+    }
+}
+
+/// The length of the DER element at the start of `der`, if it is complete
+fn first_element_len(der: &[u8]) -> Option<usize> {
+    let first = *der.get(1)?;
+    let (header, len) = if first < 0x80 {
+        (2, usize::from(first))
+    } else {
+        let count = usize::from(first & 0x7f);
+        if count == 0 || count > (usize::BITS / 8) as usize {
+            return None;
+        }
+        let bytes = der.get(2..2 + count)?;
+        let len = bytes
+            .iter()
+            .fold(0usize, |len, &b| (len << 8) | usize::from(b));
+        (2 + count, len)
+    };
+    let total = header.checked_add(len)?;
+    if total <= der.len() {
+        Some(total)
+    } else {
+        None
+    }
+}
+
+/// Parses a set of PEM-encoded data, classifying each block by its tag
+///
+/// # Example
+/// ```rust
+///  use pem::{encode_many, read_items, Item, Pem};
+///
+///  let input = encode_many(&[
+///      Pem::new("CERTIFICATE", [1, 2, 3]),
+///      Pem::new("PRIVATE KEY", [4, 5, 6]),
+///  ]);
+///  let mut certs = Vec::new();
+///  let mut key = None;
+///  for item in read_items(input).unwrap() {
+///      match item {
+///          Item::X509Certificate(der) => certs.push(der),
+///          Item::Pkcs1Key(der) | Item::Pkcs8Key(der) | Item::Sec1Key(der) => key = Some(der),
+///          _ => {}
+///      }
+///  }
+///  assert_eq!(certs, [[1, 2, 3]]);
+///  assert_eq!(key.unwrap(), [4, 5, 6]);
+/// ```
+pub fn read_items<B: AsRef<[u8]>>(input: B) -> Result<Vec<Item>> {
+    Ok(parse_many(input)?.into_iter().map(Item::from).collect())
+}
+
+/// Parses a set of PEM-encoded data, classifying each block by its tag as
+/// recognized by `matcher`
+///
+/// # Example
+/// ```rust
+///  use pem::{read_items_with, Item, TagMatcher};
+///
+///  let input = "-----BEGIN X509 CERTIFICATE-----\nAQID\n-----END X509 CERTIFICATE-----\n";
+///  let items = read_items_with(&TagMatcher::standard(), input).unwrap();
+///  assert_eq!(items, [Item::X509Certificate(vec![1, 2, 3])]);
+/// ```
+pub fn read_items_with<B: AsRef<[u8]>>(matcher: &TagMatcher, input: B) -> Result<Vec<Item>> {
+    Ok(parse_many(input)?
+        .into_iter()
+        .map(|pem| Item::from_pem_with(matcher, pem))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encode_many;
+
+    #[test]
+    fn test_read_items() {
+        let pems = [
+            Pem::new("CERTIFICATE", [1]),
+            Pem::new("RSA PRIVATE KEY", [2]),
+            Pem::new("PRIVATE KEY", [3]),
+            Pem::new("EC PRIVATE KEY", [4]),
+            Pem::new("X509 CRL", [5]),
+            Pem::new("CERTIFICATE REQUEST", [6]),
+            Pem::new("ENCRYPTED PRIVATE KEY", [7]),
+        ];
+        assert_eq!(
+            read_items(encode_many(&pems)).unwrap(),
+            [
+                Item::X509Certificate(vec![1]),
+                Item::Pkcs1Key(vec![2]),
+                Item::Pkcs8Key(vec![3]),
+                Item::Sec1Key(vec![4]),
+                Item::Crl(vec![5]),
+                Item::Csr(vec![6]),
+                Item::Other(pems[6].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trusted_certificate() {
+        let certificate = [0x30, 0x03, 0x02, 0x01, 0x05];
+        // X509_CERT_AUX with a trusted usage
+        let aux = [0x30, 0x04, 0x30, 0x02, 0x06, 0x00];
+        let trusted = Pem::new("TRUSTED CERTIFICATE", [&certificate[..], &aux].concat());
+        assert_eq!(
+            Item::from_pem_with(&TagMatcher::standard(), trusted.clone()),
+            Item::X509Certificate(certificate.to_vec())
+        );
+        assert_eq!(
+            Item::from_pem_with(&TagMatcher::new(), trusted.clone()),
+            Item::Other(trusted)
+        );
+        assert_eq!(first_element_len(&[0x30, 0x82, 0x01]), None);
+        assert_eq!(first_element_len(&[0x30, 0x81, 0x01, 0x00]), Some(4));
+    }
+
+    #[test]
+    fn test_read_items_with() {
+        let pems = [
+            Pem::new("Trusted Certificate", [1]),
+            Pem::new("new certificate request", [2]),
+            Pem::new("rsa private key", [3]),
+        ];
+        let matcher = TagMatcher::standard().set_case_insensitive(true);
+        assert_eq!(
+            read_items_with(&matcher, encode_many(&pems)).unwrap(),
+            [
+                Item::X509Certificate(vec![1]),
+                Item::Csr(vec![2]),
+                Item::Pkcs1Key(vec![3]),
+            ]
+        );
+        assert_eq!(
+            read_items_with(&TagMatcher::new(), encode_many(&pems)).unwrap(),
+            read_items(encode_many(&pems)).unwrap()
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use crate::Pem;
+use core::fmt;
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::string::{String, ToString};
+
+/// The kind of a block, as identified by its tag
+///
+/// Well-known labels, mostly from RFC 7468, have their own variant; any
+/// other tag is kept in [`SectionKind::Other`].
+///
+/// # Example
+/// ```rust
+///  use pem::{Pem, SectionKind};
+///
+///  let pem = Pem::new("EC PRIVATE KEY", [1, 2, 3]);
+///  match pem.kind() {
+///      SectionKind::PrivateKey | SectionKind::EcPrivateKey => {}
+///      kind => panic!("unexpected {kind}"),
+///  }
+///  assert_eq!(SectionKind::from("FOO"), SectionKind::Other("FOO".into()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SectionKind {
+    /// `CERTIFICATE`, an X.509 certificate
+    Certificate,
+    /// `X509 CRL`, an X.509 certificate revocation list
+    X509Crl,
+    /// `CERTIFICATE REQUEST`, a PKCS #10 certification request
+    CertificateRequest,
+    /// `PRIVATE KEY`, a PKCS #8 private key
+    PrivateKey,
+    /// `ENCRYPTED PRIVATE KEY`, a PKCS #8 encrypted private key
+    EncryptedPrivateKey,
+    /// `RSA PRIVATE KEY`, a PKCS #1 RSA private key
+    RsaPrivateKey,
+    /// `EC PRIVATE KEY`, a SEC 1 elliptic curve private key
+    EcPrivateKey,
+    /// `DSA PRIVATE KEY`, an OpenSSL DSA private key
+    DsaPrivateKey,
+    /// `OPENSSH PRIVATE KEY`, an OpenSSH private key
+    OpensshPrivateKey,
+    /// `PUBLIC KEY`, an X.509 SubjectPublicKeyInfo
+    PublicKey,
+    /// `RSA PUBLIC KEY`, a PKCS #1 RSA public key
+    RsaPublicKey,
+    /// `EC PARAMETERS`, elliptic curve domain parameters
+    EcParameters,
+    /// `DH PARAMETERS`, PKCS #3 Diffie-Hellman parameters
+    DhParameters,
+    /// `PKCS7`, a PKCS #7 / CMS structure
+    Pkcs7,
+    /// Any other tag
+    Other(String),
+}
+
+const KNOWN: &[(SectionKind, &str)] = &[
+    (SectionKind::Certificate, "CERTIFICATE"),
+    (SectionKind::X509Crl, "X509 CRL"),
+    (SectionKind::CertificateRequest, "CERTIFICATE REQUEST"),
+    (SectionKind::PrivateKey, "PRIVATE KEY"),
+    (SectionKind::EncryptedPrivateKey, "ENCRYPTED PRIVATE KEY"),
+    (SectionKind::RsaPrivateKey, "RSA PRIVATE KEY"),
+    (SectionKind::EcPrivateKey, "EC PRIVATE KEY"),
+    (SectionKind::DsaPrivateKey, "DSA PRIVATE KEY"),
+    (SectionKind::OpensshPrivateKey, "OPENSSH PRIVATE KEY"),
+    (SectionKind::PublicKey, "PUBLIC KEY"),
+    (SectionKind::RsaPublicKey, "RSA PUBLIC KEY"),
+    (SectionKind::EcParameters, "EC PARAMETERS"),
+    (SectionKind::DhParameters, "DH PARAMETERS"),
+    (SectionKind::Pkcs7, "PKCS7"),
+];
+
+impl SectionKind {
+    /// Get the tag of this kind
+    pub fn as_str(&self) -> &str {
+        match self {
+            SectionKind::Other(tag) => tag,
+            kind => KNOWN
+                .iter()
+                .find(|(known, _)| known == kind)
+                .map(|(_, tag)| *tag)
+                .expect("every well-known kind has a tag"),
+        }
+    }
+
+    /// Whether this kind holds private key material
+    pub fn is_private_key(&self) -> bool {
+        matches!(
+            self,
+            SectionKind::PrivateKey
+                | SectionKind::EncryptedPrivateKey
+                | SectionKind::RsaPrivateKey
+                | SectionKind::EcPrivateKey
+                | SectionKind::DsaPrivateKey
+                | SectionKind::OpensshPrivateKey
+        )
+    }
+}
+
+impl From<&str> for SectionKind {
+    fn from(tag: &str) -> Self {
+        KNOWN.iter().find(|(_, known)| *known == tag).map_or_else(
+            || SectionKind::Other(tag.to_string()),
+            |(kind, _)| kind.clone(),
+        )
+    }
+}
+
+impl fmt::Display for SectionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Pem {
+    /// Get the kind of the block, as identified by its tag
+    pub fn kind(&self) -> SectionKind {
+        SectionKind::from(self.tag())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_section_kind_round_trip() {
+        for (kind, tag) in KNOWN {
+            assert_eq!(SectionKind::from(*tag), *kind);
+            assert_eq!(kind.as_str(), *tag);
+            assert_eq!(Pem::new(*tag, []).kind(), *kind);
+        }
+        let other = SectionKind::from("certificate");
+        assert_eq!(other, SectionKind::Other("certificate".into()));
+        assert_eq!(other.to_string(), "certificate");
+        assert!(!other.is_private_key());
+        assert!(SectionKind::OpensshPrivateKey.is_private_key());
+    }
+}
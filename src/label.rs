@@ -0,0 +1,21 @@
+//! The RFC 7468 label grammar, shared by the strict checkers.
+
+/// `labelchar = %x21-2C / %x2E-7E`
+pub fn is_label_char(c: u8) -> bool {
+    matches!(c, 0x21..=0x2c | 0x2e..=0x7e)
+}
+
+/// `label = [ labelchar *( ["-" / SP] labelchar ) ]`
+pub fn is_valid_label(label: &[u8]) -> bool {
+    let mut after_separator = true;
+    for &c in label {
+        if is_label_char(c) {
+            after_separator = false;
+        } else if (c == b'-' || c == b' ') && !after_separator {
+            after_separator = true;
+        } else {
+            return false;
+        }
+    }
+    label.is_empty() || !after_separator
+}
@@ -0,0 +1,284 @@
+use crate::{parse_captures_iter, Captures, EncodeConfig, Pem, PemError, PemRef, Result};
+use core::str;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// A block whose contents are decoded on first access
+///
+/// Parsing only checks the framing and copies the raw headers and base64
+/// data; the data is decoded when [`LazyPem::contents`] is first called,
+/// or on [`LazyPem::decode`]. Unlike [`PemRef`], the block owns its data,
+/// so it can outlive the input.
+///
+/// # Example
+/// ```rust
+///  use pem::{encode_many, parse_many_lazy, Pem};
+///
+///  let input = encode_many(&[Pem::new("KEY", [1, 2, 3]), Pem::new("CERTIFICATE", [4, 5, 6])]);
+///  let mut blocks = parse_many_lazy(&input).unwrap();
+///  blocks.retain(|block| block.tag() == "CERTIFICATE");
+///  assert_eq!(blocks[0].contents().unwrap(), [4, 5, 6]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LazyPem {
+    tag: String,
+    headers: Vec<u8>,
+    data: Vec<u8>,
+    /// The decoded block, once the contents have been accessed
+    decoded: Option<Pem>,
+}
+
+impl LazyPem {
+    /// Create a block from its tag and the base64 encoding of its contents,
+    /// such as a body forwarded from elsewhere, without decoding it
+    ///
+    /// Like for [`parse_many_lazy`], invalid base64 data is only reported
+    /// once the contents are accessed.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use pem::{EncodeConfig, LazyPem, LineEnding};
+    ///
+    ///  let mut lazy = LazyPem::from_base64_parts("FOO", "AQID\nBAU=");
+    ///  let config = EncodeConfig::new().set_line_ending(LineEnding::LF);
+    ///  assert_eq!(
+    ///      lazy.encode_config(config)?,
+    ///      "-----BEGIN FOO-----\nAQIDBAU=\n-----END FOO-----\n"
+    ///  );
+    ///  assert_eq!(lazy.contents()?, [1, 2, 3, 4, 5]);
+    ///  assert!(LazyPem::from_base64_parts("FOO", "AQI").contents().is_err());
+    ///  # Ok::<(), pem::PemError>(())
+    /// ```
+    pub fn from_base64_parts(tag: impl ToString, base64: &str) -> LazyPem {
+        LazyPem {
+            tag: tag.to_string(),
+            headers: Vec::new(),
+            data: base64.as_bytes().to_vec(),
+            decoded: None,
+        }
+    }
+
+    /// Get the tag of the block
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Get the raw base64 data of the block, including line breaks
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the base64 data of the block without whitespace, without
+    /// decoding it
+    pub fn contents_base64(&self) -> Result<String> {
+        let data = str::from_utf8(&self.data).map_err(PemError::NotUtf8)?;
+        Ok(data.chars().filter(|c| !c.is_whitespace()).collect())
+    }
+
+    /// Encode the block with the given config, wrapping the base64 data as
+    /// it is instead of decoding and re-encoding it
+    ///
+    /// The data is not validated, except that it must be ASCII.
+    pub fn encode_config(&self, config: EncodeConfig) -> Result<String> {
+        assert!(config.line_wrap > 0, "line wrap must be non-zero");
+        let data = self.contents_base64()?;
+        if let Some((i, &b)) = data
+            .as_bytes()
+            .iter()
+            .enumerate()
+            .find(|(_, b)| !b.is_ascii())
+        {
+            return Err(PemError::InvalidData(base64::DecodeError::InvalidByte(
+                i, b,
+            )));
+        }
+        let headers = str::from_utf8(&self.headers).map_err(PemError::NotUtf8)?;
+        let line_ending = config.line_ending.as_str();
+
+        let mut output = String::with_capacity(data.len() + 64);
+        output.push_str("-----BEGIN ");
+        output.push_str(&self.tag);
+        output.push_str("-----");
+        output.push_str(line_ending);
+        let mut headers = headers
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .peekable();
+        if headers.peek().is_some() {
+            for line in headers {
+                output.push_str(line);
+                output.push_str(line_ending);
+            }
+            output.push_str(line_ending);
+        }
+        for line in data.as_bytes().chunks(config.line_wrap) {
+            output.push_str(str::from_utf8(line).expect("base64 data is ASCII"));
+            output.push_str(line_ending);
+        }
+        output.push_str("-----END ");
+        output.push_str(&self.tag);
+        output.push_str("-----");
+        if config.trailing_newline {
+            output.push_str(line_ending);
+        }
+        Ok(output)
+    }
+
+    /// Get the binary contents, decoding them on first access
+    pub fn contents(&mut self) -> Result<&[u8]> {
+        let pem = match self.decoded.take() {
+            Some(pem) => pem,
+            None => self.decode()?,
+        };
+        Ok(self.decoded.get_or_insert(pem).contents())
+    }
+
+    /// Decode the base64 data straight into `writer` a few kilobytes at a
+    /// time, returning the number of bytes written
+    ///
+    /// Unlike [`LazyPem::contents`], this doesn't keep the decoded contents,
+    /// so even huge blocks only need a small buffer. If the contents were
+    /// already decoded, they are written as they are.
+    #[cfg(feature = "std")]
+    pub fn decode_contents_to<W: Write>(&self, mut writer: W) -> io::Result<u64> {
+        match &self.decoded {
+            Some(pem) => {
+                writer.write_all(pem.contents())?;
+                Ok(pem.contents().len() as u64)
+            }
+            None => crate::buffer::decode_data_to(&self.data, writer),
+        }
+    }
+
+    /// Decode the headers and the base64 data into an owned Pem struct
+    pub fn decode(&self) -> Result<Pem> {
+        match &self.decoded {
+            Some(pem) => Ok(pem.clone()),
+            None => Pem::new_from_captures(Captures {
+                begin: self.tag.as_bytes(),
+                headers: &self.headers,
+                data: &self.data,
+                end: self.tag.as_bytes(),
+            }),
+        }
+    }
+
+    /// Consume the block to decode it into an owned Pem struct
+    pub fn into_pem(self) -> Result<Pem> {
+        match self.decoded {
+            Some(pem) => Ok(pem),
+            None => self.decode(),
+        }
+    }
+}
+
+impl PartialEq for LazyPem {
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag && self.headers == other.headers && self.data == other.data
+    }
+}
+
+impl Eq for LazyPem {}
+
+impl From<PemRef<'_>> for LazyPem {
+    fn from(pem: PemRef<'_>) -> Self {
+        LazyPem {
+            tag: pem.tag().to_string(),
+            headers: pem.raw_headers().to_vec(),
+            data: pem.raw_data().to_vec(),
+            decoded: None,
+        }
+    }
+}
+
+/// Parses a set of PEM blocks, deferring the decoding of their contents
+///
+/// Fails on the first block with broken framing, such as mismatched tags.
+/// Invalid base64 data is only reported once the contents are accessed.
+pub fn parse_many_lazy<B: AsRef<[u8]> + ?Sized>(input: &B) -> Result<Vec<LazyPem>> {
+    parse_captures_iter(input.as_ref())
+        .map(|caps| PemRef::from_captures(caps).map(LazyPem::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{parse_many, PemError};
+
+    #[test]
+    fn test_lazy_decoding() {
+        let input = "-----BEGIN FOO-----\r\n\
+                     Comment: first\r\n\
+                     \r\n\
+                     AQID\r\n\
+                     -----END FOO-----\r\n\
+                     -----BEGIN BAR-----\r\nBA?G\r\n-----END BAR-----\r\n";
+        let mut blocks = parse_many_lazy(input).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].tag(), "BAR");
+        assert_eq!(blocks[1].raw_data(), b"BA?G\r\n");
+        assert!(matches!(
+            blocks[1].contents(),
+            Err(PemError::InvalidData(_))
+        ));
+
+        let mut output = Vec::new();
+        assert_eq!(blocks[0].decode_contents_to(&mut output).unwrap(), 3);
+        assert_eq!(blocks[0].contents().unwrap(), [1, 2, 3]);
+        assert_eq!(blocks[0].contents().unwrap(), [1, 2, 3]);
+        assert_eq!(blocks[0].decode_contents_to(&mut output).unwrap(), 3);
+        assert_eq!(output, [1, 2, 3, 1, 2, 3]);
+        let decoded = blocks[0].decode().unwrap();
+        assert_eq!(decoded.headers().get("Comment"), Some("first"));
+        assert_eq!(blocks.remove(0).into_pem().unwrap(), decoded);
+
+        let valid = &input[..input.find("-----BEGIN BAR").unwrap()];
+        let pems: Vec<Pem> = parse_many_lazy(valid)
+            .unwrap()
+            .into_iter()
+            .map(LazyPem::into_pem)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(pems, parse_many(valid).unwrap());
+
+        assert_eq!(
+            parse_many_lazy("-----BEGIN FOO-----\nAQID\n-----END BAR-----\n"),
+            Err(PemError::MismatchedTags("FOO".into(), "BAR".into()))
+        );
+    }
+
+    #[test]
+    fn test_forward_base64() {
+        use crate::{encode, encode_config, LineEnding};
+
+        let mut pem = Pem::new("FOO", (0..100).collect::<Vec<u8>>());
+        pem.headers_mut().add("Comment", "first").unwrap();
+        let config = EncodeConfig::new()
+            .set_line_ending(LineEnding::LF)
+            .set_line_wrap(16);
+        for lazy in parse_many_lazy(&encode(&pem)).unwrap() {
+            assert_eq!(lazy.encode_config(config), Ok(encode_config(&pem, config)));
+        }
+
+        let mut lazy = LazyPem::from_base64_parts("FOO", &pem.contents_base64());
+        assert_eq!(lazy.contents_base64().unwrap(), pem.contents_base64());
+        assert_eq!(lazy.contents().unwrap(), pem.contents());
+        let forwarded = LazyPem::from_base64_parts("FOO", "BA?G\nBAU=");
+        assert_eq!(
+            forwarded.encode_config(EncodeConfig::new()).unwrap(),
+            "-----BEGIN FOO-----\r\nBA?GBAU=\r\n-----END FOO-----\r\n"
+        );
+        assert!(matches!(
+            LazyPem::from_base64_parts("FOO", "AQ\u{e9}D").encode_config(config),
+            Err(PemError::InvalidData(_))
+        ));
+    }
+}
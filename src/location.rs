@@ -0,0 +1,152 @@
+use crate::parser::{find, Captures};
+use crate::{Base64Policy, PemError};
+use base64::DecodeError;
+use core::{fmt, ops::Range, str};
+
+/// Where in the input an error occurred, as attached by
+/// [`ParseConfig::set_error_locations`](crate::ParseConfig::set_error_locations)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// The 1-based line number
+    pub line: usize,
+    /// The byte offset in the input
+    pub offset: usize,
+}
+
+impl Location {
+    fn new(input: &[u8], offset: usize) -> Location {
+        let line = input[..offset].iter().filter(|&&c| c == b'\n').count() + 1;
+        Location { line, offset }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, byte {}", self.line, self.offset)
+    }
+}
+
+/// The position of a block in the input, as returned by
+/// [`parse_many_with_spans`](crate::parse_many_with_spans)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// The byte range, from the start of the BEGIN boundary to the end of
+    /// the END boundary
+    pub bytes: Range<usize>,
+    /// The 1-based line numbers, from the BEGIN line up to and including
+    /// the END line
+    pub lines: Range<usize>,
+}
+
+impl Span {
+    pub(crate) fn new(input: &[u8], bytes: Range<usize>) -> Span {
+        let start = Location::new(input, bytes.start).line;
+        let end = start + input[bytes.clone()].iter().filter(|&&c| c == b'\n').count() + 1;
+        Span {
+            bytes,
+            lines: start..end,
+        }
+    }
+}
+
+/// Find the offending part of the block `caps` for an error raised while
+/// decoding it
+pub fn locate(input: &[u8], caps: &Captures, error: &PemError, policy: Base64Policy) -> Location {
+    let offset_of = |section: &[u8]| section.as_ptr() as usize - input.as_ptr() as usize;
+    let offset = match error {
+        PemError::MismatchedTags(..) | PemError::MissingEndTag => offset_of(caps.end),
+        PemError::InvalidData(e) => offset_of(caps.data) + data_offset(caps.data, e, policy),
+        PemError::InvalidHeader(header) => {
+            offset_of(caps.headers) + find(caps.headers, header.as_bytes()).unwrap_or(0)
+        }
+        PemError::NotUtf8(_) => [caps.begin, caps.end, caps.data, caps.headers]
+            .iter()
+            .find_map(|section| {
+                let e = str::from_utf8(section).err()?;
+                Some(offset_of(section) + e.valid_up_to())
+            })
+            .unwrap_or_else(|| offset_of(caps.begin)),
+        _ => offset_of(caps.begin),
+    };
+    Location::new(input, offset)
+}
+
+/// Map the position of a base64 error, which counts the data without the
+/// whitespace stripped under `policy`, back to the data
+fn data_offset(data: &[u8], error: &DecodeError, policy: Base64Policy) -> usize {
+    let last = data
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .unwrap_or(0);
+    let index = match *error {
+        DecodeError::InvalidByte(index, _) | DecodeError::InvalidLastSymbol(index, _) => index,
+        DecodeError::InvalidLength(_) | DecodeError::InvalidPadding => return last,
+    };
+    // Data that failed to decode has already been checked to be UTF-8
+    let data = str::from_utf8(data).unwrap_or_default();
+    let mut stripped = 0;
+    for (offset, c) in data.char_indices().filter(|&(_, c)| !policy.strips(c)) {
+        if stripped == index {
+            return offset;
+        }
+        stripped += c.len_utf8();
+    }
+    last
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parse_config, parse_many_config, Location, ParseConfig, PemError};
+
+    fn location(input: &str) -> Location {
+        let config = ParseConfig::new().set_error_locations(true);
+        match parse_many_config(input, &config) {
+            Err(PemError::Located(location, _)) => location,
+            result => panic!("expected a located error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_error_locations() {
+        let valid = "-----BEGIN FOO-----\nAQID\n-----END FOO-----\n";
+        let input = format!("{valid}-----BEGIN FOO-----\nAQID\nAQ?D\n-----END FOO-----\n");
+        assert_eq!(
+            location(&input),
+            Location {
+                line: 6,
+                offset: valid.len() + 27
+            }
+        );
+
+        let input = format!("{valid}-----BEGIN FOO-----\nAQID\n-----END BAR-----\n");
+        assert_eq!(location(&input).line, 6);
+
+        let input = format!("{valid}-----BEGIN FOO-----\nNo colon\n\nAQID\n-----END FOO-----\n");
+        assert_eq!(location(&input).line, 5);
+    }
+
+    #[test]
+    fn test_error_locations_are_opt_in() {
+        let input = "-----BEGIN FOO-----\nA?ID\n-----END FOO-----\n";
+        assert!(matches!(
+            parse_config(input, &ParseConfig::new()),
+            Err(PemError::InvalidData(_))
+        ));
+        let config = ParseConfig::new().set_error_locations(true);
+        let err = parse_config(input, &config).unwrap_err();
+        assert!(
+            matches!(&err, PemError::Located(_, inner) if matches!(**inner, PemError::InvalidData(_)))
+        );
+        assert!(matches!(err.inner(), PemError::InvalidData(_)));
+        assert!(matches!(
+            parse_config(input, &ParseConfig::new())
+                .unwrap_err()
+                .inner(),
+            PemError::InvalidData(_)
+        ));
+        assert_eq!(
+            err.to_string(),
+            "invalid data: Invalid symbol 63, offset 1. (line 2, byte 21)"
+        );
+    }
+}
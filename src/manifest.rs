@@ -0,0 +1,141 @@
+use crate::fingerprint::{Digest, Fingerprint};
+use crate::{parse_captures_iter, Pem, Result};
+use core::ops::Range;
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{string::String, vec::Vec};
+
+/// Describes one block of a bundle, as listed by [`manifest`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry<T> {
+    /// The tag of the block
+    pub tag: String,
+    /// The size of the binary contents in bytes
+    pub size: usize,
+    /// The fingerprint of the binary contents
+    pub fingerprint: Fingerprint<T>,
+    /// The byte range of the block in the bundle, from the start of the
+    /// BEGIN line to the end of the END line
+    pub span: Range<usize>,
+}
+
+/// Produce a manifest of a bundle, with one entry per block in input order.
+///
+/// With the `serde` feature, entries serialize as records with `tag`,
+/// `size`, `fingerprint` (colon-separated hex), `start` and `end` fields,
+/// so what was shipped can be recorded and later compared.
+///
+/// # Example
+/// ```rust
+///  use pem::{fingerprint::Digest, manifest};
+///
+///  struct Xor;
+///
+///  impl Digest for Xor {
+///      type Output = [u8; 1];
+///
+///      fn digest(data: &[u8]) -> [u8; 1] {
+///          [data.iter().fold(0, |acc, b| acc ^ b)]
+///      }
+///  }
+///
+///  let bundle = "-----BEGIN FOO-----\nAQID\n-----END FOO-----\n";
+///  let entries = manifest::<Xor, _>(bundle).unwrap();
+///  assert_eq!(entries[0].tag, "FOO");
+///  assert_eq!(entries[0].size, 3);
+///  assert_eq!(entries[0].fingerprint.to_string(), "00");
+///  assert_eq!(entries[0].span, 0..42);
+/// ```
+pub fn manifest<D: Digest, B: AsRef<[u8]>>(input: B) -> Result<Vec<ManifestEntry<D::Output>>> {
+    let mut matches = parse_captures_iter(input.as_ref());
+    let mut entries = Vec::new();
+    while let Some((span, caps)) = matches.next_spanned() {
+        let pem = Pem::new_from_captures(caps)?;
+        entries.push(ManifestEntry {
+            size: pem.contents.len(),
+            fingerprint: pem.fingerprint::<D>(),
+            tag: pem.tag,
+            span,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::ManifestEntry;
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+    impl<T: AsRef<[u8]>> Serialize for ManifestEntry<T> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            struct Display<'a, T>(&'a T);
+
+            impl<'a, T: core::fmt::Display> Serialize for Display<'a, T> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(self.0)
+                }
+            }
+
+            let mut entry = serializer.serialize_struct("ManifestEntry", 5)?;
+            entry.serialize_field("tag", self.tag.as_str())?;
+            entry.serialize_field("size", &self.size)?;
+            entry.serialize_field("fingerprint", &Display(&self.fingerprint))?;
+            entry.serialize_field("start", &self.span.start)?;
+            entry.serialize_field("end", &self.span.end)?;
+            entry.end()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PemError;
+
+    struct Len;
+
+    impl Digest for Len {
+        type Output = [u8; 1];
+
+        fn digest(data: &[u8]) -> [u8; 1] {
+            [data.len() as u8]
+        }
+    }
+
+    const BUNDLE: &str = "garbage\n\
+                          -----BEGIN FOO-----\r\nAQID\r\n-----END FOO-----\r\n\r\n\
+                          -----BEGIN BAR-----\nAQIDBA==\n-----END BAR-----";
+
+    #[test]
+    fn test_manifest_spans() {
+        let entries = manifest::<Len, _>(BUNDLE).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].tag, "FOO");
+        assert_eq!(entries[0].size, 3);
+        assert_eq!(entries[0].fingerprint.as_bytes(), [3]);
+        assert_eq!(entries[0].span, 8..52);
+        assert!(BUNDLE[entries[0].span.clone()].starts_with("-----BEGIN FOO"));
+        assert!(BUNDLE[entries[0].span.clone()].ends_with("-----END FOO-----"));
+        assert_eq!(entries[1].size, 4);
+        assert_eq!(entries[1].span.end, BUNDLE.len());
+        assert!(BUNDLE[entries[1].span.clone()].starts_with("-----BEGIN BAR"));
+
+        assert_eq!(
+            manifest::<Len, _>("-----BEGIN FOO-----\nAQID\n-----END BAR-----\n"),
+            Err(PemError::MismatchedTags("FOO".into(), "BAR".into()))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_manifest_serialize() {
+        let entries = manifest::<Len, _>(BUNDLE).unwrap();
+        assert_eq!(
+            serde_json::to_string(&entries[1]).unwrap(),
+            r#"{"tag":"BAR","size":4,"fingerprint":"04","start":56,"end":102}"#
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Input normalization for [`ParseConfig::set_normalize_input`](crate::ParseConfig::set_normalize_input).
+
+use core::str;
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::vec::Vec;
+
+/// Replace byte order marks and non-ASCII whitespace with spaces, and
+/// lone carriage returns with line feeds, then move whitespace at the end
+/// of lines after the line break, so that lines holding only whitespace
+/// are empty
+///
+/// Every replacement has the length of what it replaces, so offsets of
+/// anything but whitespace are the same as in the original input. Invalid
+/// UTF-8 is left as is.
+pub fn normalize(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut rest = input;
+    loop {
+        match str::from_utf8(rest) {
+            Ok(valid) => {
+                push_chars(&mut output, valid);
+                move_trailing_whitespace(&mut output);
+                return output;
+            }
+            Err(e) => {
+                let (valid, invalid) = rest.split_at(e.valid_up_to());
+                push_chars(&mut output, str::from_utf8(valid).unwrap_or_default());
+                let len = e.error_len().unwrap_or(invalid.len());
+                output.extend_from_slice(&invalid[..len]);
+                rest = &invalid[len..];
+            }
+        }
+    }
+}
+
+fn push_chars(output: &mut Vec<u8>, input: &str) {
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' if chars.peek() != Some(&'\n') => output.push(b'\n'),
+            '\u{feff}' => output.extend_from_slice(b"   "),
+            c if !c.is_ascii() && c.is_whitespace() => {
+                output.extend(core::iter::repeat(b' ').take(c.len_utf8()))
+            }
+            c => output.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+}
+
+fn move_trailing_whitespace(output: &mut [u8]) {
+    let mut i = 0;
+    while i < output.len() {
+        let start = i;
+        while i < output.len() && (output[i] == b' ' || output[i] == b'\t') {
+            i += 1;
+        }
+        let line_break = match &output[i..] {
+            [b'\r', b'\n', ..] => 2,
+            [b'\n', ..] => 1,
+            _ => 0,
+        };
+        output[start..i + line_break].rotate_right(line_break);
+        i += line_break.max(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(b"a\rb\r\nc\r"), b"a\nb\r\nc\n");
+        assert_eq!(normalize(b"a \t\r\n \nb \n"), b"a\r\n \t\n b\n ");
+        assert_eq!(
+            normalize("\u{feff}x\u{a0}y\u{3000}z\t".as_bytes()),
+            b"   x  y   z\t"
+        );
+        assert_eq!(normalize(b"\xff\r\xc2"), b"\xff\n\xc2");
+        let input = "caf\u{e9}\u{2003}";
+        assert_eq!(normalize(input.as_bytes()).len(), input.len());
+    }
+}
@@ -0,0 +1,199 @@
+//! OpenPGP ASCII armor (RFC 4880, section 6), which frames data like PEM
+//! but ends the body with a `=XXXX` CRC-24 checksum line.
+//!
+//! ```rust
+//!  use pem::{openpgp, EncodeConfig, LineEnding, Pem};
+//!
+//!  let mut key = Pem::new("PGP PUBLIC KEY BLOCK", [1, 2, 3]);
+//!  key.headers_mut().add("Version", "1").unwrap();
+//!  let config = EncodeConfig::new().set_line_ending(LineEnding::LF);
+//!  let armored = openpgp::encode_config(&key, config);
+//!  assert_eq!(
+//!      armored,
+//!      "-----BEGIN PGP PUBLIC KEY BLOCK-----\n\
+//!       Version: 1\n\
+//!       \n\
+//!       AQID\n\
+//!       =Z2GT\n\
+//!       -----END PGP PUBLIC KEY BLOCK-----\n"
+//!  );
+//!  assert_eq!(openpgp::parse(armored).unwrap(), key);
+//! ```
+use crate::{encode_config as encode_pem, parse_captures, parse_captures_iter};
+use crate::{Captures, EncodeConfig, Pem, PemError, Result};
+use base64::Engine as _;
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{string::String, vec::Vec};
+
+const CRC24_INIT: u32 = 0x00b7_04ce;
+const CRC24_POLY: u32 = 0x0186_4cfb;
+
+/// Compute the CRC-24 checksum of `data`, as used by OpenPGP armor
+pub fn crc24(data: &[u8]) -> u32 {
+    let mut crc = CRC24_INIT;
+    for &byte in data {
+        crc ^= u32::from(byte) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x0100_0000 != 0 {
+                crc ^= CRC24_POLY;
+            }
+        }
+    }
+    crc & 0x00ff_ffff
+}
+
+/// Parses a single armored block, verifying its checksum
+///
+/// The checksum line is optional, as in RFC 9580; if present, it must
+/// match the contents, or [`PemError::ChecksumMismatch`] is returned.
+pub fn parse<B: AsRef<[u8]>>(input: B) -> Result<Pem> {
+    parse_captures(input.as_ref())
+        .ok_or(PemError::MalformedFraming)
+        .and_then(new_from_captures)
+}
+
+/// Parses a set of armored blocks, verifying their checksums
+pub fn parse_many<B: AsRef<[u8]>>(input: B) -> Result<Vec<Pem>> {
+    parse_captures_iter(input.as_ref())
+        .map(new_from_captures)
+        .collect()
+}
+
+fn new_from_captures(caps: Captures) -> Result<Pem> {
+    let (data, checksum) = split_checksum(caps.data);
+    let pem = Pem::new_from_captures(Captures { data, ..caps })?;
+    if let Some(checksum) = checksum {
+        let mut expected = [0; 3];
+        base64::engine::general_purpose::STANDARD
+            .decode_slice(checksum, &mut expected)
+            .map_err(|_| PemError::ChecksumMismatch)?;
+        if crc24(pem.contents()).to_be_bytes()[1..] != expected {
+            return Err(PemError::ChecksumMismatch);
+        }
+    }
+    Ok(pem)
+}
+
+/// Split the `=XXXX` checksum line off the end of the body
+fn split_checksum(data: &[u8]) -> (&[u8], Option<&[u8]>) {
+    let len = data
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(0, |i| i + 1);
+    let trimmed = &data[..len];
+    let line_start = trimmed
+        .iter()
+        .rposition(|&c| c == b'\n' || c == b'\r')
+        .map_or(0, |i| i + 1);
+    match &trimmed[line_start..] {
+        [b'=', checksum @ ..] if checksum.len() == 4 => (&data[..line_start], Some(checksum)),
+        _ => (data, None),
+    }
+}
+
+/// Encode a block as armor, with the default configuration
+pub fn encode(pem: &Pem) -> String {
+    encode_config(pem, EncodeConfig::default())
+}
+
+/// Encode a block as armor, appending the checksum line to the body
+///
+/// Unlike PEM, armor always separates the headers from the body with an
+/// empty line, even if there are no headers.
+pub fn encode_config(pem: &Pem, config: EncodeConfig) -> String {
+    let line_ending = config.line_ending.as_str();
+    // The original text of a parsed block has its own line endings and
+    // blank lines, so the armor is always written afresh
+    let mut fresh = pem.clone();
+    fresh.original = None;
+    let mut output = encode_pem(&fresh, config);
+    if pem.headers().iter().next().is_none() {
+        // Comments come before the BEGIN line
+        let begin = output.find("-----BEGIN ").expect("BEGIN line");
+        let begin_len =
+            begin + output[begin..].find(line_ending).expect("BEGIN line") + line_ending.len();
+        output.insert_str(begin_len, line_ending);
+    }
+
+    let mut checksum = String::from("=");
+    base64::engine::general_purpose::STANDARD
+        .encode_string(&crc24(pem.contents()).to_be_bytes()[1..], &mut checksum);
+    checksum.push_str(line_ending);
+    let end = output.rfind("-----END ").expect("END line");
+    output.insert_str(end, &checksum);
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LineEnding;
+
+    #[test]
+    fn test_crc24() {
+        assert_eq!(crc24(b""), CRC24_INIT);
+        assert_eq!(crc24(b"Hello, world!\n"), 0x009c_8072);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let message = Pem::new("PGP MESSAGE", (0..=255).collect::<Vec<u8>>());
+        let armored = encode(&message);
+        assert!(armored.starts_with("-----BEGIN PGP MESSAGE-----\r\n\r\n"));
+        assert_eq!(parse(&armored).unwrap(), message);
+
+        let config = EncodeConfig::new().set_line_ending(LineEnding::LF);
+        let bundle = [message.clone(), Pem::new("PGP SIGNATURE", [])];
+        let armored: String = bundle
+            .iter()
+            .map(|pem| encode_config(pem, config))
+            .collect();
+        assert_eq!(parse_many(armored).unwrap(), bundle);
+    }
+
+    #[test]
+    fn test_checksum() {
+        let input = "-----BEGIN PGP MESSAGE-----\n\nAQID\n=Z2GU\n-----END PGP MESSAGE-----\n";
+        assert_eq!(parse(input), Err(PemError::ChecksumMismatch));
+
+        // The checksum is optional
+        let input = "-----BEGIN PGP MESSAGE-----\n\nAQID\n-----END PGP MESSAGE-----\n";
+        assert_eq!(parse(input).unwrap().contents(), [1, 2, 3]);
+
+        // Plain PEM parsing rejects the checksum line
+        let input = "-----BEGIN PGP MESSAGE-----\n\nAQID\n=Z2GT\n-----END PGP MESSAGE-----\n";
+        assert!(crate::parse(input).is_err());
+        assert!(parse(input).is_ok());
+    }
+
+    #[test]
+    fn test_comment() {
+        let mut pem = Pem::new("PGP MESSAGE", [1, 2, 3]);
+        pem.set_comment("note").unwrap();
+        let config = EncodeConfig::new().set_line_ending(LineEnding::LF);
+        let armored = encode_config(&pem, config);
+        assert_eq!(
+            armored,
+            "note\n-----BEGIN PGP MESSAGE-----\n\nAQID\n=Z2GT\n-----END PGP MESSAGE-----\n"
+        );
+        assert_eq!(parse(armored).unwrap(), pem);
+    }
+
+    #[test]
+    fn test_preserved_formatting() {
+        let input = "-----BEGIN PGP MESSAGE-----\n\nAQID\n-----END PGP MESSAGE-----\n";
+        let config = crate::ParseConfig::new().set_preserve_formatting(true);
+        let pem = crate::parse_config(input, &config).unwrap();
+        assert_eq!(
+            encode(&pem),
+            "-----BEGIN PGP MESSAGE-----\r\n\r\nAQID\r\n=Z2GT\r\n-----END PGP MESSAGE-----\r\n"
+        );
+        let lf = EncodeConfig::new().set_line_ending(LineEnding::LF);
+        assert_eq!(
+            encode_config(&pem, lf),
+            input.replace("AQID\n", "AQID\n=Z2GT\n")
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{parse_captures_iter, Captures, Pem, Result};
+use rayon::prelude::*;
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+/// The raw sections of a block, copied out of the input by the scanner
+struct Block {
+    begin: Vec<u8>,
+    headers: Vec<u8>,
+    data: Vec<u8>,
+    end: Vec<u8>,
+}
+
+impl Block {
+    fn decode(&self) -> Result<Pem> {
+        Pem::new_from_captures(Captures {
+            begin: &self.begin,
+            headers: &self.headers,
+            data: &self.data,
+            end: &self.end,
+        })
+    }
+}
+
+/// Ordered results of [`parse_many_pipelined`]
+///
+/// Yields the result for each block in input order, as soon as it and all
+/// blocks before it have been decoded.
+#[derive(Debug)]
+pub struct Pipeline {
+    results: mpsc::Receiver<(usize, Result<Pem>)>,
+    pending: BTreeMap<usize, Result<Pem>>,
+    next: usize,
+}
+
+impl Iterator for Pipeline {
+    type Item = Result<Pem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.pending.remove(&self.next) {
+                self.next += 1;
+                return Some(result);
+            }
+            let (index, result) = self.results.recv().ok()?;
+            self.pending.insert(index, result);
+        }
+    }
+}
+
+/// Parses a set of PEM-encoded data on a pipeline of threads.
+///
+/// One thread scans the input for block boundaries and hands each block to
+/// a pool of workers, sized after the available parallelism, which decode
+/// the base64 contents. The returned iterator yields the results in input
+/// order.
+///
+/// # Example
+/// ```rust
+///  use pem::{encode_many, parse_many_pipelined, Pem};
+///
+///  let input = encode_many(&[Pem::new("FOO", [1, 2, 3]), Pem::new("BAR", [4, 5, 6])]);
+///  let pems = parse_many_pipelined(input).collect::<pem::Result<Vec<_>>>().unwrap();
+///  assert_eq!(pems[0].tag(), "FOO");
+///  assert_eq!(pems[1].tag(), "BAR");
+/// ```
+pub fn parse_many_pipelined(input: impl Into<Vec<u8>>) -> Pipeline {
+    let input = input.into();
+    let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let (block_tx, block_rx) = mpsc::sync_channel::<(usize, Block)>(workers * 4);
+    let block_rx = Arc::new(Mutex::new(block_rx));
+    let (result_tx, result_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for (index, caps) in parse_captures_iter(&input).enumerate() {
+            let block = Block {
+                begin: caps.begin.to_vec(),
+                headers: caps.headers.to_vec(),
+                data: caps.data.to_vec(),
+                end: caps.end.to_vec(),
+            };
+            if block_tx.send((index, block)).is_err() {
+                break;
+            }
+        }
+    });
+
+    for _ in 0..workers {
+        let block_rx = Arc::clone(&block_rx);
+        let result_tx = result_tx.clone();
+        thread::spawn(move || loop {
+            let received = block_rx.lock().map(|rx| rx.recv());
+            let (index, block) = match received {
+                Ok(Ok(job)) => job,
+                _ => break,
+            };
+            if result_tx.send((index, block.decode())).is_err() {
+                break;
+            }
+        });
+    }
+
+    Pipeline {
+        results: result_rx,
+        pending: BTreeMap::new(),
+        next: 0,
+    }
+}
+
+/// Parses a set of PEM-encoded data, decoding the blocks on the rayon
+/// thread pool.
+///
+/// The input is split on the block boundaries first, then the blocks are
+/// decoded in parallel. The result is the same as for
+/// [`parse_many`](crate::parse_many), including failing on the first
+/// invalid block.
+///
+/// # Example
+/// ```rust
+///  use pem::{encode_many, parse_many_parallel, Pem};
+///
+///  let pems: Vec<Pem> = (0..100u8).map(|i| Pem::new("FOO", [i])).collect();
+///  assert_eq!(parse_many_parallel(encode_many(&pems)).unwrap(), pems);
+/// ```
+pub fn parse_many_parallel<B: AsRef<[u8]>>(input: B) -> Result<Vec<Pem>> {
+    let blocks: Vec<Captures> = parse_captures_iter(input.as_ref()).collect();
+    // Collect all results first, so the error reported is deterministically
+    // the one of the first invalid block
+    let results: Vec<Result<Pem>> = blocks.into_par_iter().map(Pem::new_from_captures).collect();
+    results.into_iter().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode, parse_many, PemError};
+
+    #[test]
+    fn test_pipeline_preserves_order() {
+        let pems: Vec<Pem> = (0..200u8)
+            .map(|i| Pem::new(format!("BLOCK {i}"), vec![i; i as usize]))
+            .collect();
+        let input: String = pems.iter().map(encode).collect();
+        let parsed: Vec<Pem> = parse_many_pipelined(input.clone())
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(parsed, pems);
+        assert_eq!(parsed, parse_many(input).unwrap());
+    }
+
+    #[test]
+    fn test_parse_many_parallel() {
+        let pems: Vec<Pem> = (0..200u8)
+            .map(|i| Pem::new(format!("BLOCK {i}"), vec![i; i as usize]))
+            .collect();
+        let input: String = pems.iter().map(encode).collect();
+        assert_eq!(parse_many_parallel(&input).unwrap(), pems);
+
+        let input = input
+            .replacen("-----END BLOCK 7-----", "-----END BLOCK X-----", 1)
+            .replacen("-----END BLOCK 9-----", "-----END BLOCK Y-----", 1);
+        assert_eq!(
+            parse_many_parallel(&input),
+            Err(PemError::MismatchedTags("BLOCK 7".into(), "BLOCK X".into()))
+        );
+    }
+
+    #[test]
+    fn test_pipeline_reports_errors_in_place() {
+        let input = "-----BEGIN FOO-----\nAQID\n-----END FOO-----\n\
+                     -----BEGIN FOO-----\nAQID\n-----END BAR-----\n\
+                     -----BEGIN BAR-----\nAQID\n-----END BAR-----\n";
+        let results: Vec<Result<Pem>> = parse_many_pipelined(input).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert_eq!(
+            results[1],
+            Err(PemError::MismatchedTags("FOO".into(), "BAR".into()))
+        );
+        assert!(results[2].is_ok());
+    }
+}
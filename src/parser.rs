@@ -1,3 +1,6 @@
+use core::ops::Range;
+
+#[derive(Clone, Copy)]
 pub struct Captures<'a> {
     pub begin: &'a [u8],
     pub headers: &'a [u8],
@@ -6,25 +9,30 @@ pub struct Captures<'a> {
 }
 
 pub fn parse_captures(input: &[u8]) -> Option<Captures<'_>> {
-    parser_inner(input).map(|(_, cap)| cap)
+    parser_inner(input).map(|(_, _, cap)| cap)
 }
 pub fn parse_captures_iter(input: &[u8]) -> CaptureMatches<'_> {
-    CaptureMatches { input }
+    CaptureMatches { input, offset: 0 }
 }
 
 pub struct CaptureMatches<'a> {
     input: &'a [u8],
+    offset: usize,
 }
-impl<'a> Iterator for CaptureMatches<'a> {
-    type Item = Captures<'a>;
-    fn next(&mut self) -> Option<Self::Item> {
+impl<'a> CaptureMatches<'a> {
+    /// Like `next`, but also returns the span of the block in the input,
+    /// from the start of the BEGIN line to the end of the END line
+    pub fn next_spanned(&mut self) -> Option<(Range<usize>, Captures<'a>)> {
         if self.input.is_empty() {
             return None;
         }
         match parser_inner(self.input) {
-            Some((remaining, captures)) => {
+            Some((remaining, span, captures)) => {
+                let consumed = self.input.len() - remaining.len();
+                let span = self.offset + span.start..self.offset + span.end;
                 self.input = remaining;
-                Some(captures)
+                self.offset += consumed;
+                Some((span, captures))
             }
             None => {
                 self.input = &[];
@@ -33,12 +41,19 @@ impl<'a> Iterator for CaptureMatches<'a> {
         }
     }
 }
+impl<'a> Iterator for CaptureMatches<'a> {
+    type Item = Captures<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_spanned().map(|(_, captures)| captures)
+    }
+}
 
-fn parse_begin(input: &[u8]) -> Option<(&[u8], &[u8])> {
-    let (input, _) = read_until(input, b"-----BEGIN ")?;
+// Also returns the length of the data skipped before the BEGIN line
+fn parse_begin(input: &[u8]) -> Option<(&[u8], usize, &[u8])> {
+    let (input, skipped) = read_until(input, b"-----BEGIN ")?;
     let (input, begin) = read_until(input, b"-----")?;
     let input = skip_whitespace(input);
-    Some((input, begin))
+    Some((input, skipped.len(), begin))
 }
 
 fn parse_payload(input: &[u8]) -> Option<(&[u8], &[u8])> {
@@ -56,12 +71,10 @@ fn extract_headers_and_data(input: &[u8]) -> (&[u8], &[u8]) {
 }
 
 fn parse_end(input: &[u8]) -> Option<(&[u8], &[u8])> {
-    let (remaining, end) = read_until(input, b"-----")?;
-    let remaining = skip_whitespace(remaining);
-    Some((remaining, end))
+    read_until(input, b"-----")
 }
 
-fn parser_inner(input: &[u8]) -> Option<(&[u8], Captures<'_>)> {
+fn parser_inner(input: &[u8]) -> Option<(&[u8], Range<usize>, Captures<'_>)> {
     // Should be equivalent to the regex
     // "(?s)-----BEGIN (?P<begin>.*?)-----[ \t\n\r]*(?P<data>.*?)-----END (?P<end>.*?)-----[ \t\n\r]*"
 
@@ -70,10 +83,13 @@ fn parser_inner(input: &[u8]) -> Option<(&[u8], Captures<'_>)> {
     // (?P<data>.*?)                             # Parse data
     // -----END (?P<end>.*?)-----[ \t\n\r]*      # Parse end
 
-    let (input, begin) = parse_begin(input)?;
+    let full = input;
+    let (input, start, begin) = parse_begin(input)?;
     let (input, payload) = parse_payload(input)?;
     let (headers, data) = extract_headers_and_data(payload);
     let (remaining, end) = parse_end(input)?;
+    let span = start..full.len() - remaining.len();
+    let remaining = skip_whitespace(remaining);
 
     let captures = Captures {
         begin,
@@ -81,7 +97,7 @@ fn parser_inner(input: &[u8]) -> Option<(&[u8], Captures<'_>)> {
         data,
         end,
     };
-    Some((remaining, captures))
+    Some((remaining, span, captures))
 }
 
 // Equivalent to the regex [ \t\n\r]*
@@ -103,20 +119,13 @@ fn read_until<'a>(input: &'a [u8], marker: &[u8]) -> Option<(&'a [u8], &'a [u8])
     if marker.is_empty() {
         return Some((&[], input));
     }
-    let mut index = 0;
-    let mut found = 0;
-    while input.len() - index >= marker.len() - found {
-        if input[index] == marker[found] {
-            found += 1;
-        } else {
-            found = 0;
-        }
-        index += 1;
-        if found == marker.len() {
-            let remaining = &input[index..];
-            let matched = &input[..index - found];
-            return Some((remaining, matched));
-        }
-    }
-    None
+    let index = find(input, marker)?;
+    Some((&input[index + marker.len()..], &input[..index]))
+}
+
+/// Find the first occurrence of `needle` in `haystack`
+pub fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
 }
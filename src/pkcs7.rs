@@ -0,0 +1,135 @@
+//! Extraction of certificates from PKCS #7 / CMS blocks.
+//!
+//! CAs frequently deliver certificate chains as `.p7b` files, i.e. a
+//! degenerate PKCS #7 SignedData structure whose only purpose is to carry a
+//! list of certificates. This module splits them into plain `CERTIFICATE`
+//! blocks.
+//!
+//! ```rust,no_run
+//!  let p7b = std::fs::read("chain.p7b.pem")?;
+//!  let chain = pem::pkcs7::expand(&pem::parse_many(p7b)?)?;
+//!  # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+use crate::der::{self, CONTEXT_0, INTEGER, OID, SEQUENCE, SET};
+use crate::{Pem, PemError, Result};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::vec::Vec;
+
+/// The tags of blocks holding PKCS #7 / CMS structures
+pub const TAGS: &[&str] = &["PKCS7", "CMS", "PKCS #7 SIGNED DATA"];
+
+/// The DER encoding of the signedData content type, 1.2.840.113549.1.7.2
+const SIGNED_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x02];
+
+/// Whether `pem` holds a PKCS #7 / CMS structure, judging by its tag
+pub fn is_pkcs7(pem: &Pem) -> bool {
+    TAGS.contains(&pem.tag())
+}
+
+/// Extract the certificates embedded in a PKCS #7 / CMS SignedData block
+/// as individual `CERTIFICATE` blocks, in the order they are stored.
+///
+/// Fails with [`PemError::UnknownTag`] if the block is not tagged as PKCS
+/// #7 / CMS, and with [`PemError::MalformedDer`] if its contents are not
+/// a SignedData structure.
+pub fn certificates(pem: &Pem) -> Result<Vec<Pem>> {
+    if !is_pkcs7(pem) {
+        return Err(PemError::UnknownTag(pem.tag().into()));
+    }
+    signed_data_certificates(pem.contents()).ok_or(PemError::MalformedDer)
+}
+
+/// Replace every PKCS #7 / CMS block in `pems` with the certificates it
+/// embeds, keeping all other blocks as they are.
+pub fn expand(pems: &[Pem]) -> Result<Vec<Pem>> {
+    let mut expanded = Vec::with_capacity(pems.len());
+    for pem in pems {
+        if is_pkcs7(pem) {
+            expanded.extend(certificates(pem)?);
+        } else {
+            expanded.push(pem.clone());
+        }
+    }
+    Ok(expanded)
+}
+
+fn signed_data_certificates(der: &[u8]) -> Option<Vec<Pem>> {
+    // ContentInfo ::= SEQUENCE { contentType, [0] EXPLICIT content }
+    let (content_info, _) = der::expect(der, SEQUENCE)?;
+    let (content_type, rest) = der::expect(content_info.value, OID)?;
+    if content_type.value != SIGNED_DATA {
+        return None;
+    }
+    let (content, _) = der::expect(rest, CONTEXT_0)?;
+
+    // SignedData ::= SEQUENCE { version, digestAlgorithms, contentInfo,
+    //     certificates [0] IMPLICIT OPTIONAL, crls [1] IMPLICIT OPTIONAL,
+    //     signerInfos }
+    let (signed_data, _) = der::expect(content.value, SEQUENCE)?;
+    let (_, rest) = der::expect(signed_data.value, INTEGER)?;
+    let (_, rest) = der::expect(rest, SET)?;
+    let (_, rest) = der::expect(rest, SEQUENCE)?;
+
+    let mut certificates = Vec::new();
+    if let Some((set, _)) = der::expect(rest, CONTEXT_0) {
+        for choice in der::children(set.value) {
+            let choice = choice?;
+            // Other CertificateChoices, e.g. attribute certificates, are
+            // context-tagged and skipped
+            if choice.tag == SEQUENCE {
+                certificates.push(Pem::new("CERTIFICATE", choice.raw));
+            }
+        }
+    }
+    Some(certificates)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::der::build::tlv;
+
+    fn p7b(certificates: &[Vec<u8>]) -> Vec<u8> {
+        let mut signed_data = tlv(INTEGER, &[1]);
+        signed_data.extend(tlv(SET, &[]));
+        signed_data.extend(tlv(SEQUENCE, &tlv(OID, &[0x2a, 0x86, 0x48])));
+        signed_data.extend(tlv(CONTEXT_0, &certificates.concat()));
+        signed_data.extend(tlv(SET, &[]));
+
+        let mut content_info = tlv(OID, SIGNED_DATA);
+        content_info.extend(tlv(CONTEXT_0, &tlv(SEQUENCE, &signed_data)));
+        tlv(SEQUENCE, &content_info)
+    }
+
+    #[test]
+    fn test_certificates() {
+        let leaf = tlv(SEQUENCE, &[0xab; 300]);
+        let root = tlv(SEQUENCE, &tlv(INTEGER, &[2]));
+        let pem = Pem::new("PKCS7", p7b(&[leaf.clone(), root.clone()]));
+        assert_eq!(
+            certificates(&pem).unwrap(),
+            [Pem::new("CERTIFICATE", leaf), Pem::new("CERTIFICATE", root)]
+        );
+
+        assert_eq!(
+            certificates(&Pem::new("CERTIFICATE", [0x30, 0])),
+            Err(PemError::UnknownTag("CERTIFICATE".into()))
+        );
+        assert_eq!(
+            certificates(&Pem::new("CMS", [0x30, 0x05, 0])),
+            Err(PemError::MalformedDer)
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        let cert = tlv(SEQUENCE, &tlv(INTEGER, &[3]));
+        let key = Pem::new("PRIVATE KEY", [1, 2, 3]);
+        let pems = [
+            Pem::new("PKCS7", p7b(std::slice::from_ref(&cert))),
+            key.clone(),
+        ];
+        assert_eq!(expand(&pems).unwrap(), [Pem::new("CERTIFICATE", cert), key]);
+    }
+}
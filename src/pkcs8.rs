@@ -0,0 +1,239 @@
+//! Conversion of private keys between PKCS #1 / SEC 1 and PKCS #8.
+//!
+//! Some consumers, such as rustls, only accept PKCS #8 `PRIVATE KEY`
+//! blocks, while OpenSSL still writes `RSA PRIVATE KEY` and
+//! `EC PRIVATE KEY` blocks. Converting between them only re-wraps the DER
+//! structure around the key, so no cryptography is involved.
+//!
+//! ```rust,no_run
+//!  let key = pem::parse(std::fs::read("key.pem")?)?;
+//!  let pkcs8 = pem::pkcs8::to_pkcs8(&key)?;
+//!  assert_eq!(pkcs8.tag(), "PRIVATE KEY");
+//!  # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+use crate::der::{self, CONTEXT_0, CONTEXT_1, INTEGER, NULL, OCTET_STRING, OID, SEQUENCE};
+use crate::{Pem, PemError, Result};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::vec::Vec;
+
+/// The DER encoding of rsaEncryption, 1.2.840.113549.1.1.1
+const RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+
+/// The DER encoding of id-ecPublicKey, 1.2.840.10045.2.1
+const EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+
+/// Convert an `RSA PRIVATE KEY` (PKCS #1) or `EC PRIVATE KEY` (SEC 1)
+/// block into a `PRIVATE KEY` (PKCS #8) block
+///
+/// `PRIVATE KEY` blocks are returned as they are. Elliptic curve keys must
+/// name their curve, which PKCS #8 moves into the algorithm identifier.
+///
+/// Fails with [`PemError::UnknownTag`] for other tags, with
+/// [`PemError::Rejected`] for keys encrypted with RFC 1421 headers, and
+/// with [`PemError::MalformedDer`] if the key cannot be parsed.
+pub fn to_pkcs8(pem: &Pem) -> Result<Pem> {
+    check_unencrypted(pem)?;
+    let algorithm = match pem.tag() {
+        "PRIVATE KEY" => return Ok(Pem::new("PRIVATE KEY", pem.contents())),
+        "RSA PRIVATE KEY" => {
+            der::expect(pem.contents(), SEQUENCE).ok_or(PemError::MalformedDer)?;
+            [der::write(OID, RSA_ENCRYPTION), der::write(NULL, &[])].concat()
+        }
+        "EC PRIVATE KEY" => {
+            let curve = ec_curve(pem.contents()).ok_or(PemError::MalformedDer)?;
+            [der::write(OID, EC_PUBLIC_KEY), curve.to_vec()].concat()
+        }
+        tag => return Err(PemError::UnknownTag(tag.into())),
+    };
+
+    // PrivateKeyInfo ::= SEQUENCE { version, privateKeyAlgorithm, privateKey }
+    let private_key_info = [
+        der::write(INTEGER, &[0]),
+        der::write(SEQUENCE, &algorithm),
+        der::write(OCTET_STRING, pem.contents()),
+    ]
+    .concat();
+    Ok(Pem::new(
+        "PRIVATE KEY",
+        der::write(SEQUENCE, &private_key_info),
+    ))
+}
+
+/// Convert a `PRIVATE KEY` (PKCS #8) block holding an RSA or elliptic
+/// curve key into an `RSA PRIVATE KEY` (PKCS #1) or `EC PRIVATE KEY`
+/// (SEC 1) block
+///
+/// The curve of elliptic curve keys is added to the SEC 1 structure if it
+/// was only named in the algorithm identifier.
+///
+/// Fails with [`PemError::UnknownTag`] for other tags, with
+/// [`PemError::UnsupportedAlgorithm`] for keys of other algorithms, such as
+/// Ed25519, which have no other format, and with [`PemError::MalformedDer`]
+/// if the key cannot be parsed.
+pub fn from_pkcs8(pem: &Pem) -> Result<Pem> {
+    check_unencrypted(pem)?;
+    if pem.tag() != "PRIVATE KEY" {
+        return Err(PemError::UnknownTag(pem.tag().into()));
+    }
+    let (algorithm, key) = private_key_info(pem.contents()).ok_or(PemError::MalformedDer)?;
+    let (oid, parameters) = der::expect(algorithm, OID).ok_or(PemError::MalformedDer)?;
+    match oid.value {
+        RSA_ENCRYPTION => Ok(Pem::new("RSA PRIVATE KEY", key)),
+        EC_PUBLIC_KEY => {
+            let (curve, _) = der::expect(parameters, OID).ok_or(PemError::MalformedDer)?;
+            let key = with_ec_curve(key, curve.raw).ok_or(PemError::MalformedDer)?;
+            Ok(Pem::new("EC PRIVATE KEY", key))
+        }
+        oid => Err(PemError::UnsupportedAlgorithm(der::oid_to_string(oid))),
+    }
+}
+
+fn check_unencrypted(pem: &Pem) -> Result<()> {
+    if pem.headers().get("Proc-Type").is_some() {
+        return Err(PemError::Rejected(
+            pem.tag().into(),
+            "encrypted keys cannot be converted".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Get the algorithm identifier contents and the private key of a
+/// PrivateKeyInfo
+fn private_key_info(der: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (info, _) = der::expect(der, SEQUENCE)?;
+    let (_, rest) = der::expect(info.value, INTEGER)?;
+    let (algorithm, rest) = der::expect(rest, SEQUENCE)?;
+    let (key, _) = der::expect(rest, OCTET_STRING)?;
+    Some((algorithm.value, key.value))
+}
+
+/// Split an ECPrivateKey into the elements before its `[0]` parameters,
+/// the parameters, if any, and the elements after them
+fn ec_private_key(der: &[u8]) -> Option<(&[u8], Option<der::Tlv<'_>>, &[u8])> {
+    // ECPrivateKey ::= SEQUENCE { version, privateKey OCTET STRING,
+    //     parameters [0] OPTIONAL, publicKey [1] OPTIONAL }
+    let (key, _) = der::expect(der, SEQUENCE)?;
+    let (_, rest) = der::expect(key.value, INTEGER)?;
+    let (_, rest) = der::expect(rest, OCTET_STRING)?;
+    let head = &key.value[..key.value.len() - rest.len()];
+    match der::expect(rest, CONTEXT_0) {
+        Some((parameters, tail)) => Some((head, Some(parameters), tail)),
+        None => Some((head, None, rest)),
+    }
+}
+
+/// Get the encoded curve OID named in the parameters of an ECPrivateKey
+fn ec_curve(der: &[u8]) -> Option<&[u8]> {
+    let (_, parameters, _) = ec_private_key(der)?;
+    let (curve, _) = der::expect(parameters?.value, OID)?;
+    Some(curve.raw)
+}
+
+/// Add the encoded `curve` to the parameters of an ECPrivateKey if it has
+/// none
+fn with_ec_curve(der: &[u8], curve: &[u8]) -> Option<Vec<u8>> {
+    match ec_private_key(der)? {
+        (_, Some(_), _) => Some(der.to_vec()),
+        (head, None, tail) => {
+            if !tail.is_empty() {
+                der::expect(tail, CONTEXT_1)?;
+            }
+            let key = [head, &der::write(CONTEXT_0, curve), tail].concat();
+            Some(der::write(SEQUENCE, &key))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::der::build::tlv;
+
+    /// The encoded OID of prime256v1, 1.2.840.10045.3.1.7
+    const P256: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+    fn ec_key(parameters: bool) -> Vec<u8> {
+        let mut key = [tlv(INTEGER, &[1]), tlv(OCTET_STRING, &[0x42; 32])].concat();
+        if parameters {
+            key.extend(tlv(CONTEXT_0, P256));
+        }
+        key.extend(tlv(CONTEXT_1, &tlv(0x03, &[0, 4, 1, 2])));
+        tlv(SEQUENCE, &key)
+    }
+
+    #[test]
+    fn test_rsa_round_trip() {
+        let pkcs1 = Pem::new(
+            "RSA PRIVATE KEY",
+            tlv(
+                SEQUENCE,
+                &[tlv(INTEGER, &[0]), tlv(INTEGER, &[0x55; 200])].concat(),
+            ),
+        );
+        let pkcs8 = to_pkcs8(&pkcs1).unwrap();
+        assert_eq!(pkcs8.tag(), "PRIVATE KEY");
+        let (algorithm, key) = private_key_info(pkcs8.contents()).unwrap();
+        assert_eq!(
+            algorithm,
+            [tlv(OID, RSA_ENCRYPTION), tlv(NULL, &[])].concat()
+        );
+        assert_eq!(key, pkcs1.contents());
+        assert_eq!(from_pkcs8(&pkcs8).unwrap(), pkcs1);
+        assert_eq!(to_pkcs8(&pkcs8).unwrap(), pkcs8);
+    }
+
+    #[test]
+    fn test_ec_round_trip() {
+        let sec1 = Pem::new("EC PRIVATE KEY", ec_key(true));
+        let pkcs8 = to_pkcs8(&sec1).unwrap();
+        let (algorithm, _) = private_key_info(pkcs8.contents()).unwrap();
+        assert_eq!(algorithm, [&tlv(OID, EC_PUBLIC_KEY)[..], P256].concat());
+        assert_eq!(from_pkcs8(&pkcs8).unwrap(), sec1);
+
+        // Keys from PKCS #8 often only name the curve in the algorithm
+        let info = [
+            tlv(INTEGER, &[0]),
+            tlv(SEQUENCE, algorithm),
+            tlv(OCTET_STRING, &ec_key(false)),
+        ]
+        .concat();
+        let pkcs8 = Pem::new("PRIVATE KEY", tlv(SEQUENCE, &info));
+        assert_eq!(from_pkcs8(&pkcs8).unwrap(), sec1);
+
+        assert_eq!(
+            to_pkcs8(&Pem::new("EC PRIVATE KEY", ec_key(false))),
+            Err(PemError::MalformedDer)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let ed25519 = [
+            tlv(INTEGER, &[0]),
+            tlv(SEQUENCE, &tlv(OID, &[0x2b, 0x65, 0x70])),
+            tlv(OCTET_STRING, &tlv(OCTET_STRING, &[7; 32])),
+        ]
+        .concat();
+        assert_eq!(
+            from_pkcs8(&Pem::new("PRIVATE KEY", tlv(SEQUENCE, &ed25519))),
+            Err(PemError::UnsupportedAlgorithm("1.3.101.112".into()))
+        );
+        assert_eq!(
+            to_pkcs8(&Pem::new("CERTIFICATE", [0x30, 0])),
+            Err(PemError::UnknownTag("CERTIFICATE".into()))
+        );
+        assert_eq!(
+            from_pkcs8(&Pem::new("PRIVATE KEY", [0x30, 0x03, 2, 1])),
+            Err(PemError::MalformedDer)
+        );
+
+        let mut encrypted = Pem::new("RSA PRIVATE KEY", [0x30, 0]);
+        encrypted
+            .headers_mut()
+            .add("Proc-Type", "4,ENCRYPTED")
+            .unwrap();
+        assert!(matches!(to_pkcs8(&encrypted), Err(PemError::Rejected(..))));
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{Event, Pem, PemDecoder, PemError};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Read};
+
+/// Incrementally parses PEM-encoded data from a reader
+///
+/// Yields each block as soon as it has been read completely, so bundles
+/// can be consumed from sockets or files without reading them into memory
+/// first. Only the block being read is buffered, up to a configurable
+/// limit. Like [`parse_many`](crate::parse_many), text between blocks and
+/// an incomplete block at the end of the input are ignored.
+///
+/// Read errors are passed through; blocks that fail to decode, or that
+/// exceed the size limit, are reported with kind `InvalidData`.
+///
+/// # Example
+/// ```rust
+///  use pem::PemReader;
+///
+///  let input = "-----BEGIN FOO-----\nAQID\n-----END FOO-----\n\
+///               -----BEGIN BAR-----\nBAUG\n-----END BAR-----\n";
+///  let mut reader = PemReader::new(input.as_bytes());
+///  assert_eq!(reader.next().unwrap()?.tag(), "FOO");
+///  assert_eq!(reader.next().unwrap()?.tag(), "BAR");
+///  assert!(reader.next().is_none());
+///  # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct PemReader<R> {
+    reader: R,
+    decoder: PemDecoder,
+    pending: VecDeque<Event>,
+    done: bool,
+}
+
+impl<R: Read> PemReader<R> {
+    /// Create a new reader parsing the data read from `reader`
+    pub fn new(reader: R) -> Self {
+        PemReader {
+            reader,
+            decoder: PemDecoder::new(),
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Set the maximum number of bytes buffered for a single block, 16 MiB
+    /// by default
+    pub fn set_max_block_len(mut self, max_block_len: usize) -> Self {
+        self.decoder = PemDecoder::new().set_max_block_len(max_block_len);
+        self
+    }
+
+    /// Consume the PemReader to get the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for PemReader<R> {
+    type Item = io::Result<Pem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = [0; 8192];
+        loop {
+            match self.pending.pop_front() {
+                Some(Event::Block(pem)) => return Some(Ok(pem)),
+                Some(Event::Error(e)) => {
+                    // Stop reading, as the block being read exceeds the
+                    // size limit
+                    if let PemError::TooLarge(_) = e {
+                        self.done = true;
+                        self.pending.clear();
+                    }
+                    return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e)));
+                }
+                None if self.done => return None,
+                None => {}
+            }
+
+            match self.reader.read(&mut chunk) {
+                Ok(0) => self.done = true,
+                Ok(n) => self.pending.extend(self.decoder.feed(&chunk[..n])),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the blocks assembled from a sequence of lines, created by
+/// [`parse_from_lines`] and [`parse_from_bufread`]
+#[derive(Debug)]
+pub struct ParseLines<I> {
+    lines: I,
+    decoder: PemDecoder,
+    pending: VecDeque<Event>,
+}
+
+impl<I: Iterator<Item = io::Result<String>>> Iterator for ParseLines<I> {
+    type Item = io::Result<Pem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.pending.pop_front() {
+                Some(Event::Block(pem)) => return Some(Ok(pem)),
+                Some(Event::Error(e)) => {
+                    return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e)))
+                }
+                None => {}
+            }
+            let mut line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            line.push('\n');
+            self.pending.extend(self.decoder.feed(line.as_bytes()));
+        }
+    }
+}
+
+/// Parses the blocks in a sequence of lines, without line endings, such as
+/// the lines of a line-oriented protocol.
+///
+/// Yields each block as soon as its END line has been read. Like
+/// [`parse_many`](crate::parse_many), lines between blocks and an
+/// incomplete block at the end are ignored. Errors from the lines are
+/// passed through; blocks that fail to decode are reported with kind
+/// `InvalidData`.
+///
+/// # Example
+/// ```rust
+///  use pem::parse_from_lines;
+///
+///  let lines = ["220 ready", "-----BEGIN FOO-----", "AQID", "-----END FOO-----", "250 ok"];
+///  let pems = parse_from_lines(lines.iter().map(|line| Ok(line.to_string())))
+///      .collect::<std::io::Result<Vec<_>>>()?;
+///  assert_eq!(pems[0].contents(), [1, 2, 3]);
+///  # Ok::<(), std::io::Error>(())
+/// ```
+pub fn parse_from_lines<I>(lines: I) -> ParseLines<I::IntoIter>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+{
+    ParseLines {
+        lines: lines.into_iter(),
+        decoder: PemDecoder::new(),
+        pending: VecDeque::new(),
+    }
+}
+
+/// Parses the blocks read line by line from a buffered reader, see
+/// [`parse_from_lines`]
+///
+/// # Example
+/// ```rust
+///  use pem::parse_from_bufread;
+///  use std::io::BufReader;
+///
+///  let input = "-----BEGIN FOO-----\r\nAQID\r\n-----END FOO-----\r\nmore text\r\n";
+///  let pems = parse_from_bufread(BufReader::new(input.as_bytes()))
+///      .collect::<std::io::Result<Vec<_>>>()?;
+///  assert_eq!(pems[0].tag(), "FOO");
+///  # Ok::<(), std::io::Error>(())
+/// ```
+pub fn parse_from_bufread<R: BufRead>(reader: R) -> ParseLines<io::Lines<R>> {
+    parse_from_lines(reader.lines())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode, parse_many};
+
+    /// Hands out the data in tiny pieces, like a slow socket
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.0.len()).min(3);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_reader_matches_parse_many() {
+        let input: String = (0..20u8)
+            .map(|i| format!("text {i}\n{}", encode(&Pem::new("BLOCK", vec![i; 100]))))
+            .collect();
+        let pems = PemReader::new(Trickle(input.as_bytes()))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(pems, parse_many(&input).unwrap());
+    }
+
+    #[test]
+    fn test_reader_errors() {
+        let input = "-----BEGIN FOO-----\nAQID\n-----END BAR-----\n\
+                     -----BEGIN FOO-----\nAQID\n-----END FOO-----\n";
+        let results: Vec<_> = PemReader::new(input.as_bytes()).collect();
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].as_ref().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(results[1].is_ok());
+
+        let input = encode(&Pem::new("FOO", vec![0; 1000]));
+        let mut reader = PemReader::new(input.as_bytes()).set_max_block_len(100);
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_parse_from_lines() {
+        let input: String = (0..20u8)
+            .map(|i| format!("text {i}\n{}", encode(&Pem::new("BLOCK", vec![i; 100]))))
+            .collect();
+        let pems = parse_from_bufread(input.as_bytes())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(pems, parse_many(&input).unwrap());
+
+        let lines = vec![
+            Ok("-----BEGIN FOO-----".to_string()),
+            Ok("AQID".to_string()),
+            Ok("-----END BAR-----".to_string()),
+            Err(io::Error::new(io::ErrorKind::Other, "disconnected")),
+        ];
+        let results: Vec<_> = parse_from_lines(lines).collect();
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].as_ref().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(
+            results[1].as_ref().unwrap_err().kind(),
+            io::ErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn test_parse_from_lines_large_block() {
+        let pem = Pem::new("BIG", vec![7; 4 * 1024 * 1024]);
+        let input = encode(&pem);
+        let pems = parse_from_bufread(input.as_bytes())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(pems, [pem]);
+    }
+}
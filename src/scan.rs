@@ -0,0 +1,109 @@
+use crate::parser::find;
+use crate::{parse_captures_iter, Pem, Result};
+use core::ops::Range;
+
+const BEGIN: &[u8] = b"-----BEGIN ";
+
+/// Iterator over the blocks embedded in binary data, created by [`scan`]
+#[derive(Debug, Clone)]
+pub struct Scan<'a> {
+    input: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for Scan<'a> {
+    type Item = (Range<usize>, Result<Pem>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.offset + find(&self.input[self.offset..], BEGIN)?;
+            let rest = &self.input[start..];
+            if !has_label(&rest[BEGIN.len()..]) {
+                self.offset = start + 1;
+                continue;
+            }
+
+            let (span, caps) = parse_captures_iter(rest).next_spanned()?;
+            let span = start + span.start..start + span.end;
+            let result = Pem::new_from_captures(caps);
+            // A broken candidate may have swallowed the start of a valid
+            // block, so only skip past it if it decoded
+            self.offset = if result.is_ok() { span.end } else { start + 1 };
+            return Some((span, result));
+        }
+    }
+}
+
+/// Find PEM blocks embedded anywhere in arbitrary binary data, such as
+/// firmware images, core dumps or container layers.
+///
+/// Yields the byte range of each candidate block, from the start of the
+/// BEGIN line to the end of the END line, along with the decoding result.
+/// Candidates are only considered if the BEGIN line has a printable label.
+/// The range of a candidate that fails to decode may overlap the blocks
+/// that follow it.
+///
+/// # Example
+/// ```rust
+///  use pem::{encode, scan, Pem};
+///
+///  let mut image = vec![0x7f, b'E', b'L', b'F', 0, 0xff];
+///  image.extend_from_slice(encode(&Pem::new("CERTIFICATE", [1, 2, 3])).as_bytes());
+///  image.extend_from_slice(&[0, 0, 0xfe]);
+///
+///  let found: Vec<_> = scan(&image).collect();
+///  assert_eq!(found.len(), 1);
+///  assert_eq!(found[0].0.start, 6);
+///  assert_eq!(found[0].1.as_ref().unwrap().contents(), [1, 2, 3]);
+/// ```
+pub fn scan(input: &[u8]) -> Scan<'_> {
+    Scan { input, offset: 0 }
+}
+
+/// Whether `input` starts with a printable label terminated by dashes
+fn has_label(input: &[u8]) -> bool {
+    find(input, b"-----").map_or(false, |end| {
+        input[..end].iter().all(|b| (0x20..0x7f).contains(b))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode, PemError};
+
+    #[test]
+    fn test_scan_binary() {
+        let foo = Pem::new("FOO", [1, 2, 3]);
+        let bar = Pem::new("BAR", [4, 5, 6]);
+        let mut input = b"\x00-----BEGIN \xff\xfe-----\x01-----BEGIN X-Y\x00".to_vec();
+        let foo_start = input.len();
+        input.extend_from_slice(encode(&foo).as_bytes());
+        input.extend_from_slice(b"\x00\x80\x81");
+        let bar_start = input.len();
+        input.extend_from_slice(encode(&bar).as_bytes());
+
+        let found: Vec<_> = scan(&input).collect();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0.start, foo_start);
+        assert_eq!(&input[found[0].0.end - 5..found[0].0.end], b"-----");
+        assert_eq!(found[0].1, Ok(foo));
+        assert_eq!(found[1].0.start, bar_start);
+        assert_eq!(found[1].1, Ok(bar));
+    }
+
+    #[test]
+    fn test_scan_recovers_after_errors() {
+        let input = b"-----BEGIN FOO-----\n\xff\n\
+                      -----BEGIN BAR-----\nBAUG\n-----END BAR-----\n";
+        let found: Vec<_> = scan(input).collect();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, 0..input.len() - 1);
+        assert_eq!(
+            found[0].1,
+            Err(PemError::MismatchedTags("FOO".into(), "BAR".into()))
+        );
+        assert_eq!(found[1].0.start, 22);
+        assert_eq!(found[1].1, Ok(Pem::new("BAR", [4, 5, 6])));
+    }
+}
@@ -0,0 +1,130 @@
+//! Uniform loading of PEM-encoded data from configurable sources.
+//!
+//! Applications can hold a `Box<dyn PemSource>` describing where e.g. a
+//! certificate comes from, and resolve it without caring whether it is a
+//! file, an environment variable or an inline value. Custom sources, such
+//! as secret stores, only need to implement [`PemSource::load`].
+//!
+//! ```rust
+//!  use pem::source::{EnvSource, FileSource, InlineSource, PemSource};
+//!
+//!  fn source_from_config(kind: &str, value: &str) -> Box<dyn PemSource> {
+//!      match kind {
+//!          "file" => Box::new(FileSource::new(value)),
+//!          "env" => Box::new(EnvSource::new(value)),
+//!          _ => Box::new(InlineSource::new(value)),
+//!      }
+//!  }
+//!
+//!  let source = source_from_config("inline", "-----BEGIN FOO-----\nAQID\n-----END FOO-----\n");
+//!  assert_eq!(source.resolve().unwrap()[0].tag(), "FOO");
+//! ```
+use crate::{parse_many, unescape_newlines, Pem};
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A source of PEM-encoded data
+pub trait PemSource: fmt::Debug {
+    /// Load the raw PEM-encoded data
+    fn load(&self) -> io::Result<Vec<u8>>;
+
+    /// Load and parse the PEM-encoded data
+    fn resolve(&self) -> io::Result<Vec<Pem>> {
+        parse_many(self.load()?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// PEM-encoded data stored in a file
+#[derive(Debug, Clone)]
+pub struct FileSource(PathBuf);
+
+impl FileSource {
+    /// Create a new source reading the file at `path`
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        FileSource(path.as_ref().to_path_buf())
+    }
+}
+
+impl PemSource for FileSource {
+    fn load(&self) -> io::Result<Vec<u8>> {
+        fs::read(&self.0)
+    }
+}
+
+/// PEM-encoded data stored in an environment variable
+///
+/// Line breaks written as literal `\n` escapes are unescaped.
+#[derive(Debug, Clone)]
+pub struct EnvSource(String);
+
+impl EnvSource {
+    /// Create a new source reading the environment variable `name`
+    pub fn new(name: impl ToString) -> Self {
+        EnvSource(name.to_string())
+    }
+}
+
+impl PemSource for EnvSource {
+    fn load(&self) -> io::Result<Vec<u8>> {
+        let value = env::var(&self.0)
+            .map_err(|e| io::Error::new(io::ErrorKind::NotFound, format!("{}: {}", self.0, e)))?;
+        Ok(unescape_newlines(&value).unwrap_or(value).into_bytes())
+    }
+}
+
+/// PEM-encoded data given inline, e.g. in a configuration file
+#[derive(Debug, Clone)]
+pub struct InlineSource(Vec<u8>);
+
+impl InlineSource {
+    /// Create a new source holding `data`
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        InlineSource(data.into())
+    }
+}
+
+impl PemSource for InlineSource {
+    fn load(&self) -> io::Result<Vec<u8>> {
+        Ok(self.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_env_source() {
+        let name = format!("PEM_SOURCE_TEST_{}", std::process::id());
+        env::set_var(&name, "-----BEGIN FOO-----\\nAQID\\n-----END FOO-----\\n");
+        let pems = EnvSource::new(&name).resolve().unwrap();
+        env::remove_var(&name);
+        assert_eq!(pems, [Pem::new("FOO", [1, 2, 3])]);
+
+        let err = EnvSource::new(&name).resolve().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_sources_are_object_safe() {
+        let path = env::temp_dir().join(format!("pem-source-{}.pem", std::process::id()));
+        fs::write(&path, "-----BEGIN BAR-----\nBAUG\n-----END BAR-----\n").unwrap();
+        let sources: Vec<Box<dyn PemSource>> = vec![
+            Box::new(FileSource::new(&path)),
+            Box::new(InlineSource::new(
+                "-----BEGIN BAR-----\nBAUG\n-----END BAR-----\n",
+            )),
+        ];
+        for source in &sources {
+            assert_eq!(source.resolve().unwrap(), [Pem::new("BAR", [4, 5, 6])]);
+        }
+        fs::remove_file(&path).unwrap();
+
+        let err = InlineSource::new("-----BEGIN BAR-----\nBA?G\n-----END BAR-----\n")
+            .resolve()
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
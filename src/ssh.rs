@@ -0,0 +1,201 @@
+//! The SSH public key file format (RFC 4716), which frames public keys
+//! exported by e.g. PuTTY and Tectia with four-dash boundaries and allows
+//! headers to continue over several lines.
+//!
+//! ```rust
+//!  use pem::ssh;
+//!
+//!  let input = "---- BEGIN SSH2 PUBLIC KEY ----\n\
+//!               Comment: \"1024-bit RSA, converted from OpenSSH by \\\n\
+//!               me@example.com\"\n\
+//!               AQID\n\
+//!               ---- END SSH2 PUBLIC KEY ----\n";
+//!  let key = ssh::parse(input).unwrap();
+//!  assert_eq!(key.tag(), "SSH2 PUBLIC KEY");
+//!  assert_eq!(
+//!      key.headers().get("Comment"),
+//!      Some("\"1024-bit RSA, converted from OpenSSH by me@example.com\"")
+//!  );
+//!  assert_eq!(key.contents(), [1, 2, 3]);
+//!  assert_eq!(ssh::parse(ssh::encode(&key)).unwrap(), key);
+//! ```
+use crate::{decode_data, Base64Policy, EncodeConfig, HeaderMap, Pem, PemError, Result};
+use base64::Engine as _;
+use core::str;
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// The maximum length of a line, without the line ending
+const MAX_LINE_LEN: usize = 72;
+
+/// Parses the first key in the input
+pub fn parse<B: AsRef<[u8]>>(input: B) -> Result<Pem> {
+    let input = str::from_utf8(input.as_ref()).map_err(PemError::NotUtf8)?;
+    let mut lines = input.lines();
+    parse_block(&mut lines)?.ok_or(PemError::MalformedFraming)
+}
+
+/// Parses all keys in the input, ignoring the text between them
+pub fn parse_many<B: AsRef<[u8]>>(input: B) -> Result<Vec<Pem>> {
+    let input = str::from_utf8(input.as_ref()).map_err(PemError::NotUtf8)?;
+    let mut lines = input.lines();
+    let mut pems = Vec::new();
+    while let Some(pem) = parse_block(&mut lines)? {
+        pems.push(pem);
+    }
+    Ok(pems)
+}
+
+/// Get the label of a boundary line, such as `---- BEGIN SSH2 PUBLIC KEY ----`
+fn boundary<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    line.trim_end()
+        .strip_prefix("---- ")?
+        .strip_prefix(kind)?
+        .strip_prefix(' ')?
+        .strip_suffix(" ----")
+}
+
+/// Parse the next block of `lines`, if there is one
+fn parse_block<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Result<Option<Pem>> {
+    let tag = match lines.find_map(|line| boundary(line, "BEGIN")) {
+        Some(tag) => tag,
+        None => return Ok(None),
+    };
+
+    let mut headers = Vec::new();
+    let mut data = String::new();
+    loop {
+        let line = lines.next().ok_or(PemError::MissingEndTag)?;
+        if let Some(end) = boundary(line, "END") {
+            if end != tag {
+                return Err(PemError::MismatchedTags(tag.into(), end.into()));
+            }
+            break;
+        }
+        // Base64 never contains a colon, so these are headers
+        if data.is_empty() && line.contains(':') {
+            let mut header = line.to_string();
+            while header.ends_with('\\') {
+                header.pop();
+                header.push_str(lines.next().ok_or(PemError::MissingEndTag)?);
+            }
+            headers.push(header);
+        } else {
+            data.push_str(line);
+        }
+    }
+
+    let contents = decode_data(&data, Base64Policy::default())?;
+    let mut pem = Pem::new(tag, contents);
+    pem.headers = HeaderMap::parse(headers)?;
+    Ok(Some(pem))
+}
+
+/// Encode a key, with the default configuration
+pub fn encode(pem: &Pem) -> String {
+    encode_config(pem, EncodeConfig::default())
+}
+
+/// Encode a key, splitting headers longer than a line with continuation
+/// backslashes
+///
+/// The body is wrapped at the configured width, which must not exceed 72.
+pub fn encode_config(pem: &Pem, config: EncodeConfig) -> String {
+    assert!(
+        config.line_wrap > 0 && config.line_wrap <= MAX_LINE_LEN,
+        "line wrap must be between 1 and 72"
+    );
+    let line_ending = config.line_ending.as_str();
+    let mut output = format!("---- BEGIN {} ----{}", pem.tag(), line_ending);
+    for header in &pem.headers().0 {
+        let mut rest = header.trim();
+        while rest.len() > MAX_LINE_LEN {
+            let mut split = MAX_LINE_LEN - 1;
+            while !rest.is_char_boundary(split) {
+                split -= 1;
+            }
+            output.push_str(&rest[..split]);
+            output.push('\\');
+            output.push_str(line_ending);
+            rest = &rest[split..];
+        }
+        output.push_str(rest);
+        output.push_str(line_ending);
+    }
+
+    let data = base64::engine::general_purpose::STANDARD.encode(pem.contents());
+    for line in data.as_bytes().chunks(config.line_wrap) {
+        output.push_str(str::from_utf8(line).expect("base64 is ASCII"));
+        output.push_str(line_ending);
+    }
+    output.push_str(&format!("---- END {} ----{}", pem.tag(), line_ending));
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LineEnding;
+
+    #[test]
+    fn test_rfc4716_example() {
+        let input = "---- BEGIN SSH2 PUBLIC KEY ----\r\n\
+                     Comment: \"1024-bit RSA, converted from OpenSSH by me@example.com\"\r\n\
+                     x-command: /home/me/bin/lock-in-guest.sh\r\n\
+                     AAAAB3NzaC1yc2EAAAABIwAAAIEA1on8gxCGJJWSRT4uOrR13mUaUk0hRf4RzxSZ1zRb\r\n\
+                     YYFw8pfGesIFoEuVth4HKyF8k1y4mRUnYHP1XNMNMJl1JcEArC2asV8sHf6zSPVffozZ\r\n\
+                     5TT4SfsUu/iKy9lUcCfXzwre4WWZSXXcPff+EHtWshahu3WzBdnGxm5Xoi89zcE=\r\n\
+                     ---- END SSH2 PUBLIC KEY ----\r\n";
+        let key = parse(input).unwrap();
+        assert_eq!(key.tag(), "SSH2 PUBLIC KEY");
+        assert_eq!(key.contents().len(), 149);
+        assert_eq!(
+            key.headers().get("x-command"),
+            Some("/home/me/bin/lock-in-guest.sh")
+        );
+        assert_eq!(parse(encode(&key)).unwrap(), key);
+    }
+
+    #[test]
+    fn test_long_headers() {
+        let mut key = Pem::new("SSH2 PUBLIC KEY", vec![7; 100]);
+        let comment = format!("\"{}\"", "é".repeat(60));
+        key.headers_mut().add("Comment", &comment).unwrap();
+        let config = EncodeConfig::new().set_line_ending(LineEnding::LF);
+        let encoded = encode_config(&key, config);
+        assert!(encoded.lines().all(|line| line.len() <= MAX_LINE_LEN));
+        assert_eq!(
+            encoded.lines().filter(|line| line.ends_with('\\')).count(),
+            1
+        );
+        assert_eq!(parse(&encoded).unwrap(), key);
+
+        let two = format!(
+            "{encoded}text\n{}",
+            encode(&Pem::new("SSH2 PUBLIC KEY", [1]))
+        );
+        assert_eq!(parse_many(two).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            parse("---- BEGIN SSH2 PUBLIC KEY ----\nAQID\n---- END FOO ----\n"),
+            Err(PemError::MismatchedTags(
+                "SSH2 PUBLIC KEY".into(),
+                "FOO".into()
+            ))
+        );
+        assert_eq!(
+            parse("---- BEGIN SSH2 PUBLIC KEY ----\nAQID\n"),
+            Err(PemError::MissingEndTag)
+        );
+        assert_eq!(parse("AQID"), Err(PemError::MalformedFraming));
+        assert!(parse_many("").unwrap().is_empty());
+    }
+}
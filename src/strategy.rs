@@ -0,0 +1,64 @@
+//! `proptest` strategies generating valid PEM blocks.
+//!
+//! The generated labels follow the RFC 7468 grammar, so every generated
+//! block survives an encoding round trip.
+//!
+//! ```rust
+//!  use pem::strategy::pem;
+//!  use proptest::prelude::*;
+//!
+//!  proptest!(|(block in pem())| {
+//!      prop_assert_eq!(pem::parse(pem::encode(&block)).unwrap(), block);
+//!  });
+//! ```
+use crate::Pem;
+use core::ops::Range;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// Generate labels following the RFC 7468 grammar, such as `X509 CRL`
+pub fn label() -> impl Strategy<Value = String> {
+    "[!-,.-~]([- ]?[!-,.-~]){0,31}"
+}
+
+/// Generate blocks with a valid label, no headers and up to 1 KiB of
+/// contents
+pub fn pem() -> impl Strategy<Value = Pem> {
+    pem_with(label(), 0..1024)
+}
+
+/// Generate blocks with labels from `labels` and contents with a length in
+/// `sizes`
+///
+/// # Example
+/// ```rust
+///  use pem::strategy::pem_with;
+///  use proptest::prelude::*;
+///
+///  let certificates = pem_with(Just("CERTIFICATE".to_string()), 1..64);
+///  proptest!(|(block in certificates)| {
+///      prop_assert_eq!(block.tag(), "CERTIFICATE");
+///      prop_assert!(!block.contents().is_empty());
+///  });
+/// ```
+pub fn pem_with(
+    labels: impl Strategy<Value = String>,
+    sizes: Range<usize>,
+) -> impl Strategy<Value = Pem> {
+    (labels, vec(any::<u8>(), sizes)).prop_map(|(label, contents)| Pem::new(label, contents))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode, label::is_valid_label, parse};
+
+    proptest! {
+        #[test]
+        fn test_generated_blocks_round_trip(block in pem()) {
+            prop_assert!(!block.tag().is_empty());
+            prop_assert!(is_valid_label(block.tag().as_bytes()));
+            prop_assert_eq!(parse(encode(&block)).unwrap(), block);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Generators and corruptors for writing tests against PEM-handling code.
+//!
+//! [`Generator`] produces random but valid [`Pem`] blocks from a seed, so
+//! failures are reproducible. The corruptors take an encoded block and
+//! damage it in a targeted way.
+//!
+//! ```rust
+//!  use pem::test_utils::{flip_base64_char, truncate_end_line, Generator};
+//!
+//!  let mut generator = Generator::new(42).set_tags(["CERTIFICATE"]).set_sizes(1..512);
+//!  let pem = generator.generate();
+//!  let encoded = pem::encode(&pem);
+//!
+//!  assert_ne!(pem::parse(flip_base64_char(&encoded, 7)).unwrap(), pem);
+//!  assert!(pem::parse(truncate_end_line(&encoded)).is_err());
+//! ```
+use crate::Pem;
+use core::ops::Range;
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Generator of random but valid Pem blocks
+///
+/// The generator is also an endless iterator of blocks.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    /// SplitMix64 state
+    state: u64,
+    /// Tags to pick from
+    tags: Vec<String>,
+    /// Range of the length of the binary contents
+    sizes: Range<usize>,
+    /// Maximum number of headers per block
+    max_headers: usize,
+}
+
+impl Generator {
+    /// Create a new generator from a seed
+    pub fn new(seed: u64) -> Self {
+        Generator {
+            state: seed,
+            tags: ["CERTIFICATE", "PRIVATE KEY", "PUBLIC KEY", "X509 CRL"]
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            sizes: 0..1024,
+            max_headers: 0,
+        }
+    }
+
+    /// Set the tags to pick from.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tags` is empty.
+    pub fn set_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: ToString,
+    {
+        self.tags = tags.into_iter().map(|tag| tag.to_string()).collect();
+        assert!(!self.tags.is_empty(), "no tags to generate");
+        self
+    }
+
+    /// Set the range of the length of the binary contents.
+    pub fn set_sizes(mut self, sizes: Range<usize>) -> Self {
+        self.sizes = sizes;
+        self
+    }
+
+    /// Set the maximum number of headers added to each block.
+    pub fn set_max_headers(mut self, max_headers: usize) -> Self {
+        self.max_headers = max_headers;
+        self
+    }
+
+    /// Generate a new block
+    pub fn generate(&mut self) -> Pem {
+        let index = self.below(self.tags.len());
+        let tag = self.tags[index].clone();
+        let len = self.sizes.start + self.below(self.sizes.len().max(1));
+        let contents: Vec<u8> = (0..len).map(|_| self.next_u64() as u8).collect();
+
+        let mut pem = Pem::new(tag, contents);
+        for i in 0..self.below(self.max_headers + 1) {
+            let value = format!("{:X}", self.next_u64());
+            pem.headers_mut()
+                .add(&format!("X-Header-{i}"), &value)
+                .expect("generated headers are valid");
+        }
+        pem
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+impl Iterator for Generator {
+    type Item = Pem;
+
+    fn next(&mut self) -> Option<Pem> {
+        Some(self.generate())
+    }
+}
+
+/// Replace one base64 character in the body of an encoded block with a
+/// different base64 character, picked using `seed`.
+///
+/// Only the first character of a 4-character quantum is replaced, so the
+/// result still decodes, but to different contents.
+///
+/// # Panics
+///
+/// Panics if the block has an empty body.
+pub fn flip_base64_char(encoded: &str, seed: u64) -> String {
+    let mut generator = Generator::new(seed);
+    let quanta: Vec<usize> = body_positions(encoded).step_by(4).collect();
+    assert!(!quanta.is_empty(), "block has no base64 body");
+
+    let pos = quanta[generator.below(quanta.len())];
+    let mut bytes = encoded.as_bytes().to_vec();
+    let index = BASE64_ALPHABET
+        .iter()
+        .position(|&c| c == bytes[pos])
+        .expect("body character is base64");
+    bytes[pos] = BASE64_ALPHABET[(index + 1 + generator.below(63)) % 64];
+    String::from_utf8(bytes).expect("replaced an ASCII character")
+}
+
+/// Cut an encoded block off in the middle of its END line.
+pub fn truncate_end_line(encoded: &str) -> String {
+    match encoded.rfind("-----END ") {
+        Some(start) => {
+            let line_len = encoded[start..]
+                .find(['\r', '\n'])
+                .unwrap_or(encoded.len() - start);
+            encoded[..start + line_len / 2].to_string()
+        }
+        None => encoded.to_string(),
+    }
+}
+
+/// Byte offsets of the base64 characters in the body of an encoded block
+fn body_positions(encoded: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut offset = 0;
+    encoded
+        .split_inclusive('\n')
+        .map(move |line| {
+            let start = offset;
+            offset += line.len();
+            (start, line)
+        })
+        .skip_while(|(_, line)| !line.starts_with("-----BEGIN "))
+        .skip(1)
+        .take_while(|(_, line)| !line.starts_with("-----END "))
+        .filter(|(_, line)| !line.contains(':'))
+        .flat_map(|(start, line)| {
+            line.bytes()
+                .enumerate()
+                .filter(|&(_, c)| BASE64_ALPHABET.contains(&c))
+                .map(move |(i, _)| start + i)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode, parse, PemError};
+
+    #[test]
+    fn test_generated_blocks_round_trip() {
+        let generator = Generator::new(1).set_sizes(0..300).set_max_headers(3);
+        for pem in generator.take(50) {
+            assert_eq!(parse(encode(&pem)).unwrap(), pem);
+        }
+    }
+
+    #[test]
+    fn test_generator_is_deterministic() {
+        let first: Vec<Pem> = Generator::new(7).take(5).collect();
+        let second: Vec<Pem> = Generator::new(7).take(5).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_corruptors() {
+        let generator = Generator::new(3).set_sizes(1..100).set_max_headers(2);
+        for (seed, pem) in generator.take(50).enumerate() {
+            let encoded = encode(&pem);
+            let flipped = parse(flip_base64_char(&encoded, seed as u64)).unwrap();
+            assert_eq!(flipped.tag(), pem.tag());
+            assert_ne!(flipped.contents(), pem.contents());
+            assert_eq!(
+                parse(truncate_end_line(&encoded)),
+                Err(PemError::MalformedFraming)
+            );
+        }
+    }
+}
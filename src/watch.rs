@@ -0,0 +1,259 @@
+use crate::{parse_many, PemBundle};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Configuration for [`PemWatcher`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchConfig {
+    /// How often the files are checked for changes
+    interval: Duration,
+
+    /// How long a file has to stay unchanged before it is reloaded
+    debounce: Duration,
+}
+
+impl WatchConfig {
+    /// Create a new watch config with default values: files are checked
+    /// every second and reloaded once they stayed unchanged for 500 ms.
+    pub const fn new() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            debounce: Duration::from_millis(500),
+        }
+    }
+
+    /// Set how often the files are checked for changes
+    pub const fn set_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set how long a file has to stay unchanged before it is reloaded, so
+    /// that files being written are not read half-way
+    pub const fn set_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An update delivered by [`PemWatcher`]: the path of a file and its blocks
+pub type WatchEvent = (PathBuf, PemBundle);
+
+/// Watches PEM files and delivers their blocks whenever they change, e.g.
+/// to hot-reload the certificates of a long-running TLS server
+///
+/// A background thread polls the modification time and size of every file.
+/// Once a changed file has stayed unchanged for the configured debounce
+/// time, it is parsed, and its blocks are delivered if it holds at least
+/// one block and no invalid data. Files that cannot be read or parsed,
+/// such as a file in the middle of being replaced, are skipped until their
+/// next change, so receivers only ever see valid bundles. A bundle equal
+/// to the last one delivered for the same file is not delivered again.
+///
+/// The thread stops when the watcher is dropped.
+///
+/// # Example
+/// ```rust,no_run
+///  let watcher = pem::PemWatcher::new(["cert.pem", "key.pem"])?;
+///  while let Some((path, bundle)) = watcher.recv() {
+///      println!("reloading {} blocks from {}", bundle.len(), path.display());
+///  }
+///  # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct PemWatcher {
+    events: Receiver<WatchEvent>,
+    /// Dropping the sender stops the thread
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// The state of a watched file
+struct Watched {
+    path: PathBuf,
+    /// The modification time and size last seen
+    stamp: Option<(SystemTime, u64)>,
+    /// When the file was last seen changing, if it was not reloaded since
+    changed: Option<Instant>,
+    /// The bundle last delivered
+    delivered: Option<PemBundle>,
+}
+
+impl PemWatcher {
+    /// Start watching `paths` with the default [`WatchConfig`]
+    ///
+    /// The current blocks of every file are delivered first. Fails if a
+    /// file cannot be read, or with an error of kind `InvalidData` naming
+    /// the file if it holds no blocks or invalid data.
+    pub fn new<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> io::Result<Self> {
+        Self::new_config(paths, WatchConfig::new())
+    }
+
+    /// Start watching `paths` with the given config
+    ///
+    /// See [`PemWatcher::new`].
+    pub fn new_config<P: AsRef<Path>>(
+        paths: impl IntoIterator<Item = P>,
+        config: WatchConfig,
+    ) -> io::Result<Self> {
+        let (events, receiver) = mpsc::channel();
+        let mut watched = Vec::new();
+        for path in paths {
+            let path = path.as_ref().to_path_buf();
+            let stamp = stamp(&path)?;
+            let bundle = load(&path)?;
+            // The receiver is alive, so this cannot fail
+            let _ = events.send((path.clone(), bundle.clone()));
+            watched.push(Watched {
+                path,
+                stamp: Some(stamp),
+                changed: None,
+                delivered: Some(bundle),
+            });
+        }
+
+        let (stop, stopped) = mpsc::channel();
+        let thread = thread::spawn(move || poll(watched, config, &events, &stopped));
+        Ok(Self {
+            events: receiver,
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+
+    /// Wait for the next update
+    ///
+    /// Returns `None` if the watching thread stopped.
+    pub fn recv(&self) -> Option<WatchEvent> {
+        self.events.recv().ok()
+    }
+
+    /// Wait at most `timeout` for the next update
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<WatchEvent> {
+        self.events.recv_timeout(timeout).ok()
+    }
+
+    /// Get the next update if one is pending, without waiting
+    pub fn try_recv(&self) -> Option<WatchEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+impl Drop for PemWatcher {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn poll(
+    mut watched: Vec<Watched>,
+    config: WatchConfig,
+    events: &Sender<WatchEvent>,
+    stopped: &Receiver<()>,
+) {
+    while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(config.interval) {
+        let now = Instant::now();
+        for file in &mut watched {
+            let stamp = stamp(&file.path).ok();
+            if stamp != file.stamp {
+                file.stamp = stamp;
+                file.changed = Some(now);
+                continue;
+            }
+            match file.changed {
+                Some(changed) if now.duration_since(changed) >= config.debounce => {
+                    file.changed = None;
+                }
+                _ => continue,
+            }
+            let bundle = match load(&file.path) {
+                Ok(bundle) => bundle,
+                Err(_) => continue,
+            };
+            if file.delivered.as_ref() == Some(&bundle) {
+                continue;
+            }
+            if events.send((file.path.clone(), bundle.clone())).is_err() {
+                return;
+            }
+            file.delivered = Some(bundle);
+        }
+    }
+}
+
+fn stamp(path: &Path) -> io::Result<(SystemTime, u64)> {
+    let metadata = fs::metadata(path)?;
+    Ok((metadata.modified()?, metadata.len()))
+}
+
+fn load(path: &Path) -> io::Result<PemBundle> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        let message = format!("{}: {}", path.display(), e);
+        io::Error::new(io::ErrorKind::InvalidData, message)
+    };
+    let pems = parse_many(fs::read(path)?).map_err(|e| invalid(&e))?;
+    if pems.is_empty() {
+        return Err(invalid(&"no PEM blocks"));
+    }
+    Ok(PemBundle::from(pems))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{encode, encode_many, Pem};
+
+    #[test]
+    fn test_watcher() {
+        let dir = std::env::temp_dir().join(format!("pem-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cert.pem");
+        let first = Pem::new("CERTIFICATE", [1]);
+        fs::write(&path, encode(&first)).unwrap();
+
+        let config = WatchConfig::new()
+            .set_interval(Duration::from_millis(10))
+            .set_debounce(Duration::from_millis(50));
+        let watcher = PemWatcher::new_config([&path], config).unwrap();
+        let missing = PemWatcher::new([dir.join("missing.pem")]);
+        let initial = watcher.try_recv();
+
+        let second = [Pem::new("CERTIFICATE", [2; 10]), first.clone()];
+        fs::write(&path, encode_many(&second)).unwrap();
+        let reloaded = watcher.recv_timeout(Duration::from_secs(10));
+
+        // Invalid contents are never delivered
+        fs::write(&path, "-----BEGIN CERTIFICATE-----\nAQ").unwrap();
+        let invalid = watcher.recv_timeout(Duration::from_millis(300));
+        fs::write(&path, encode(&first)).unwrap();
+        let restored = watcher.recv_timeout(Duration::from_secs(10));
+        drop(watcher);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(missing.unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(
+            initial,
+            Some((path.clone(), PemBundle::from(vec![first.clone()])))
+        );
+        assert_eq!(
+            reloaded,
+            Some((path.clone(), PemBundle::from(second.to_vec())))
+        );
+        assert_eq!(invalid, None);
+        assert_eq!(restored, Some((path, PemBundle::from(vec![first]))));
+    }
+}
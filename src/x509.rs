@@ -0,0 +1,180 @@
+//! Ordering of X.509 certificate chains.
+//!
+//! Servers are routinely handed chains in the wrong order, or with
+//! certificates that belong to another chain. [`order_chain`] sorts a chain
+//! leaf first by matching the issuer of each certificate to the subject of
+//! the next, using just enough DER parsing to find the two names.
+//!
+//! ```rust,no_run
+//!  let bundle = std::fs::read("fullchain.pem")?;
+//!  let chain = pem::x509::order_chain(&pem::parse_many(bundle)?)?;
+//!  std::fs::write("fullchain.pem", pem::encode_many(&chain))?;
+//!  # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+use crate::der::{self, CONTEXT_0, SEQUENCE};
+use crate::{Pem, PemError, Result};
+
+#[cfg(not(any(feature = "std", test)))]
+use alloc::{vec, vec::Vec};
+
+/// The subject and issuer names of a certificate, as raw DER
+struct Names<'a> {
+    subject: &'a [u8],
+    issuer: &'a [u8],
+}
+
+impl Names<'_> {
+    fn is_self_issued(&self) -> bool {
+        self.subject == self.issuer
+    }
+}
+
+/// Sort a certificate chain leaf first, followed by the certificate that
+/// issued it, and so on up to the root or the last certificate available
+///
+/// Names are matched by comparing their DER encodings, which is what CAs
+/// produce in practice. A root certificate at the end of the chain is kept
+/// if present, but not required.
+///
+/// Fails with [`PemError::UnexpectedTag`] for blocks that are not
+/// `CERTIFICATE`s, with [`PemError::MalformedDer`] if a certificate cannot
+/// be parsed, and with [`PemError::UnchainedCertificate`] holding the index
+/// of the first certificate that is not part of the chain, either because
+/// it belongs to another chain or because an intermediate certificate
+/// between it and the leaf is missing.
+pub fn order_chain(pems: &[Pem]) -> Result<Vec<Pem>> {
+    let names = pems
+        .iter()
+        .map(|pem| {
+            if pem.tag() != "CERTIFICATE" {
+                return Err(PemError::UnexpectedTag(
+                    "CERTIFICATE".into(),
+                    pem.tag().into(),
+                ));
+            }
+            names(pem.contents()).ok_or(PemError::MalformedDer)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // The leaf is the certificate that issued no other, and only a lone
+    // root is its own leaf
+    let issued_other = |i: usize| {
+        names
+            .iter()
+            .enumerate()
+            .any(|(j, other)| i != j && !other.is_self_issued() && other.issuer == names[i].subject)
+    };
+    let leaf = (0..pems.len())
+        .filter(|&i| !issued_other(i))
+        .min_by_key(|&i| names[i].is_self_issued());
+    let leaf = match leaf {
+        Some(leaf) => leaf,
+        None if pems.is_empty() => return Ok(Vec::new()),
+        None => return Err(PemError::UnchainedCertificate(0)),
+    };
+
+    let mut order = vec![leaf];
+    let mut current = leaf;
+    while !names[current].is_self_issued() {
+        let issuer = (0..pems.len())
+            .find(|&i| !order.contains(&i) && names[i].subject == names[current].issuer);
+        match issuer {
+            Some(issuer) => {
+                order.push(issuer);
+                current = issuer;
+            }
+            None => break,
+        }
+    }
+
+    if let Some(unchained) = (0..pems.len()).find(|i| !order.contains(i)) {
+        return Err(PemError::UnchainedCertificate(unchained));
+    }
+    Ok(order.into_iter().map(|i| pems[i].clone()).collect())
+}
+
+fn names(certificate: &[u8]) -> Option<Names<'_>> {
+    // Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signature }
+    let (certificate, _) = der::expect(certificate, SEQUENCE)?;
+    let (tbs, _) = der::expect(certificate.value, SEQUENCE)?;
+    // TBSCertificate ::= SEQUENCE { [0] version OPTIONAL, serialNumber,
+    //     signature, issuer, validity, subject, ... }
+    let mut rest = tbs.value;
+    if let Some((_, after)) = der::expect(rest, CONTEXT_0) {
+        rest = after;
+    }
+    let (_, rest) = der::read(rest)?;
+    let (_, rest) = der::expect(rest, SEQUENCE)?;
+    let (issuer, rest) = der::expect(rest, SEQUENCE)?;
+    let (_, rest) = der::expect(rest, SEQUENCE)?;
+    let (subject, _) = der::expect(rest, SEQUENCE)?;
+    Some(Names {
+        subject: subject.raw,
+        issuer: issuer.raw,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::der::build::tlv;
+    use crate::der::{INTEGER, SET};
+
+    fn name(cn: &str) -> Vec<u8> {
+        // Name ::= SEQUENCE OF SET OF AttributeTypeAndValue
+        let common_name = [0x06, 0x03, 0x55, 0x04, 0x03];
+        let value = [&common_name[..], &tlv(0x0c, cn.as_bytes())].concat();
+        tlv(SEQUENCE, &tlv(SET, &tlv(SEQUENCE, &value)))
+    }
+
+    fn certificate(subject: &str, issuer: &str) -> Pem {
+        let tbs = [
+            tlv(CONTEXT_0, &tlv(INTEGER, &[2])),
+            tlv(INTEGER, &[1]),
+            tlv(SEQUENCE, &[]),
+            name(issuer),
+            tlv(SEQUENCE, &[]),
+            name(subject),
+            tlv(SEQUENCE, &[]),
+        ]
+        .concat();
+        let contents = [tlv(SEQUENCE, &tbs), tlv(SEQUENCE, &[]), tlv(0x03, &[0])].concat();
+        Pem::new("CERTIFICATE", tlv(SEQUENCE, &contents))
+    }
+
+    #[test]
+    fn test_order_chain() {
+        let leaf = certificate("example.com", "Intermediate");
+        let intermediate = certificate("Intermediate", "Root");
+        let root = certificate("Root", "Root");
+        let ordered = [leaf.clone(), intermediate.clone(), root.clone()];
+
+        let shuffled = [root.clone(), leaf.clone(), intermediate.clone()];
+        assert_eq!(order_chain(&shuffled).unwrap(), ordered);
+        let without_root = [intermediate.clone(), leaf.clone()];
+        assert_eq!(order_chain(&without_root).unwrap(), ordered[..2]);
+        assert_eq!(order_chain(&ordered[2..]).unwrap(), ordered[2..]);
+        assert!(order_chain(&[]).unwrap().is_empty());
+
+        let other = certificate("other.example", "Other CA");
+        assert_eq!(
+            order_chain(&[intermediate.clone(), leaf.clone(), other]),
+            Err(PemError::UnchainedCertificate(2))
+        );
+        assert_eq!(
+            order_chain(&[root, leaf.clone()]),
+            Err(PemError::UnchainedCertificate(0))
+        );
+        assert_eq!(
+            order_chain(&[leaf, Pem::new("PRIVATE KEY", [1])]),
+            Err(PemError::UnexpectedTag(
+                "CERTIFICATE".into(),
+                "PRIVATE KEY".into()
+            ))
+        );
+        assert_eq!(
+            order_chain(&[Pem::new("CERTIFICATE", [0x30, 0x00])]),
+            Err(PemError::MalformedDer)
+        );
+    }
+}